@@ -0,0 +1,241 @@
+//! activity.rs – app-wide registry of running background tasks, and the
+//! mailbox their results come back through
+use std::{
+    any::Any,
+    collections::{HashMap, VecDeque},
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+    thread,
+};
+
+pub use crossbeam_channel::TryRecvError;
+
+/// One running task as the Tasks window sees it.
+#[derive(Clone)]
+pub struct TaskInfo {
+    pub id:       u64,
+    pub label:    String,
+    /// 0.0‒1.0 once the task knows how far along it is
+    pub progress: Option<f32>,
+    /// raised by the Tasks window's Cancel button
+    pub cancel:   Option<Arc<AtomicBool>>,
+}
+
+/// A task started with `Activity::spawn`. Pages keep this instead of a
+/// channel and collect what the worker posts with `Activity::try_take`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct TaskId(u64);
+
+/// What a spawned task posted and nobody has taken yet.
+#[derive(Default)]
+struct Inbox {
+    msgs:   VecDeque<Box<dyn Any + Send>>,
+    /// the worker is done; nothing more will arrive
+    closed: bool,
+}
+
+#[derive(Default)]
+struct Registry {
+    next_id: u64,
+    tasks:   Vec<TaskInfo>,
+    inboxes: HashMap<u64, Inbox>,
+}
+
+/// Shared task list behind the menu-bar spinner and the Tasks window. Clone freely.
+#[derive(Clone, Default)]
+pub struct Activity(Arc<Mutex<Registry>>);
+
+fn lock(reg: &Mutex<Registry>) -> MutexGuard<'_, Registry> {
+    reg.lock().unwrap_or_else(|p| p.into_inner())
+}
+
+impl Activity {
+    /// Registers a task under `label`; it counts as running until the guard drops.
+    /// Move the guard into the worker thread so a panic still unregisters it.
+    pub fn begin(&self, label: impl Into<String>) -> ActivityGuard {
+        let mut reg = lock(&self.0);
+        let id = reg.next_id;
+        reg.next_id += 1;
+        reg.tasks.push(TaskInfo { id, label: label.into(), progress: None, cancel: None });
+        ActivityGuard { reg: self.0.clone(), id }
+    }
+
+    /// Runs `work` on a worker thread as a task labelled `label`. The worker
+    /// reports through its guard (label, progress, `post`); the caller keeps
+    /// the id and collects the posts with `try_take`. The task ends when
+    /// `work` returns or panics.
+    pub fn spawn(&self, label: impl Into<String>, work: impl FnOnce(&ActivityGuard) + Send + 'static) -> TaskId {
+        let guard = self.start(label);
+        let id = guard.task();
+        thread::spawn(move || work(&guard));
+        id
+    }
+
+    /// `begin` with a mailbox: for work that posts results but runs
+    /// somewhere `spawn` doesn't fit. Dropping the guard closes the mailbox.
+    pub fn start(&self, label: impl Into<String>) -> ActivityGuard {
+        let guard = self.begin(label);
+        lock(&self.0).inboxes.insert(guard.id, Inbox::default());
+        guard
+    }
+
+    /// Next message task `id` posted, oldest first, the way `try_recv`
+    /// works on a channel: `Empty` while the task still runs,
+    /// `Disconnected` once it ended and everything was taken.
+    pub fn try_take<T: 'static>(&self, id: TaskId) -> Result<T, TryRecvError> {
+        let mut reg = lock(&self.0);
+        let Some(inbox) = reg.inboxes.get_mut(&id.0) else { return Err(TryRecvError::Disconnected) };
+        match inbox.msgs.pop_front() {
+            Some(msg) => msg.downcast().map(|m| *m).map_err(|_| {
+                debug_assert!(false, "task {id:?} posted a message of another type");
+                TryRecvError::Empty
+            }),
+            None if inbox.closed => {
+                reg.inboxes.remove(&id.0);
+                Err(TryRecvError::Disconnected)
+            }
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    /// Stops collecting task `id`: what it posted and still posts is
+    /// dropped. The worker itself runs on until it notices a cancel flag.
+    pub fn forget(&self, id: TaskId) {
+        lock(&self.0).inboxes.remove(&id.0);
+    }
+
+    pub fn count(&self) -> usize {
+        lock(&self.0).tasks.len()
+    }
+
+    /// The running tasks, oldest first.
+    pub fn tasks(&self) -> Vec<TaskInfo> {
+        lock(&self.0).tasks.clone()
+    }
+}
+
+pub struct ActivityGuard {
+    reg: Arc<Mutex<Registry>>,
+    id:  u64,
+}
+
+impl ActivityGuard {
+    /// The task this guard belongs to, for `Activity::try_take`.
+    pub fn task(&self) -> TaskId {
+        TaskId(self.id)
+    }
+
+    /// Hands `msg` to whoever collects this task with `try_take`; dropped
+    /// when nobody does (a plain `begin` task, or one that was forgotten).
+    pub fn post<T: Send + 'static>(&self, msg: T) {
+        if let Some(inbox) = lock(&self.reg).inboxes.get_mut(&self.id) {
+            inbox.msgs.push_back(Box::new(msg));
+        }
+    }
+
+    fn update(&self, f: impl FnOnce(&mut TaskInfo)) {
+        if let Some(task) = lock(&self.reg).tasks.iter_mut().find(|t| t.id == self.id) {
+            f(task);
+        }
+    }
+
+    pub fn set_label(&self, label: impl Into<String>) {
+        let label = label.into();
+        self.update(|t| t.label = label);
+    }
+
+    pub fn set_progress(&self, frac: f32) {
+        self.update(|t| t.progress = Some(frac.clamp(0.0, 1.0)));
+    }
+
+    /// Lets the Tasks window stop this task by raising `flag`.
+    pub fn set_cancel(&self, flag: Arc<AtomicBool>) {
+        self.update(|t| t.cancel = Some(flag));
+    }
+}
+
+impl Drop for ActivityGuard {
+    fn drop(&mut self) {
+        let mut reg = lock(&self.reg);
+        reg.tasks.retain(|t| t.id != self.id);
+        if let Some(inbox) = reg.inboxes.get_mut(&self.id) {
+            inbox.closed = true;
+        }
+    }
+}
+
+/// Raises a task's cancel flag (the worker notices on its next check).
+pub fn cancel(task: &TaskInfo) {
+    if let Some(flag) = &task.cancel {
+        flag.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Runs a worker body, turning a panic into an `Err` so the page that is
+/// waiting on the task gets an answer instead of spinning forever.
+pub fn catch_panic<T, E: From<String>>(f: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|p| {
+        let msg = p
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| p.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown cause".into());
+        Err(format!("internal error: {msg}").into())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tasks_report_progress_until_their_guard_drops() {
+        let activity = Activity::default();
+        let a = activity.begin("a");
+        let b = activity.begin("b");
+        b.set_progress(1.5);
+        let tasks = activity.tasks();
+        assert_eq!(tasks.iter().map(|t| t.label.as_str()).collect::<Vec<_>>(), ["a", "b"]);
+        assert_eq!(tasks[1].progress, Some(1.0));
+        drop(a);
+        assert_eq!(activity.count(), 1);
+        assert_eq!(activity.tasks()[0].label, "b");
+    }
+
+    #[test]
+    fn posts_are_taken_in_order_then_the_task_disconnects() {
+        let activity = Activity::default();
+        let guard = activity.start("fetch");
+        let id = guard.task();
+        assert_eq!(activity.try_take::<u32>(id), Err(TryRecvError::Empty));
+        guard.post(1u32);
+        guard.post(2u32);
+        drop(guard);
+        assert_eq!(activity.try_take::<u32>(id), Ok(1));
+        assert_eq!(activity.try_take::<u32>(id), Ok(2));
+        assert_eq!(activity.try_take::<u32>(id), Err(TryRecvError::Disconnected));
+        assert_eq!(activity.count(), 0);
+
+        // a forgotten task's results go nowhere
+        let forgotten = activity.start("stale");
+        activity.forget(forgotten.task());
+        forgotten.post(3u32);
+        assert_eq!(activity.try_take::<u32>(forgotten.task()), Err(TryRecvError::Disconnected));
+    }
+
+    #[test]
+    fn spawned_work_posts_from_its_thread() {
+        let activity = Activity::default();
+        let id = activity.spawn("sum", |task| task.post(catch_panic(|| Ok::<u32, String>(2 + 2))));
+        let got = loop {
+            match activity.try_take::<Result<u32, String>>(id) {
+                Err(TryRecvError::Empty) => thread::yield_now(),
+                other => break other,
+            }
+        };
+        assert_eq!(got, Ok(Ok(4)));
+    }
+}
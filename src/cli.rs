@@ -0,0 +1,115 @@
+//! cli.rs – headless subcommands, parsed before the GUI starts
+use std::{
+    io::{self, Write},
+    sync::atomic::AtomicBool,
+    thread,
+};
+
+use crossbeam_channel::unbounded;
+
+use crate::{
+    disk,
+    launch::{self, LaunchOptions},
+    net::{self, ProgressEvent},
+    process,
+    pages::{
+        instances::InstancesPage,
+        versions,
+    },
+};
+
+const USAGE: &str = "\
+usage: vs_launcher [command]
+
+commands:
+  launch <instance>    start the game for an instance
+  list-instances       print instance names and versions
+  download <version>   download and extract a game version
+
+Without a command the launcher window opens.";
+
+/// Runs a subcommand if one was given. `None` means "start the GUI".
+pub fn run(args: &[String]) -> Option<i32> {
+    let cmd = args.first()?;
+    let code = match (cmd.as_str(), args.get(1)) {
+        ("list-instances", None) => list_instances(),
+        ("launch", Some(name)) => launch_instance(name),
+        ("download", Some(ver)) => download(ver),
+        ("help" | "--help" | "-h", _) => {
+            println!("{USAGE}");
+            0
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            2
+        }
+    };
+    Some(code)
+}
+
+fn list_instances() -> i32 {
+    for inst in InstancesPage::load_instances() {
+        println!("{}\t{}", inst.name, inst.version);
+    }
+    0
+}
+
+fn launch_instance(name: &str) -> i32 {
+    let instances = InstancesPage::load_instances();
+    let Some(inst) = instances.iter().find(|i| i.name == name) else {
+        eprintln!("no instance named {name:?}");
+        return 1;
+    };
+    if let Some(pid) = process::external_game_using(&inst.data_dir(), &[]) {
+        eprintln!("warning: {name} already looks to be running (pid {pid}) with the same data folder");
+    }
+    match launch::launch(inst, LaunchOptions { capture: false, world: None }) {
+        Ok(_) => {
+            println!("Launched {}", inst.name);
+            0
+        }
+        Err(msg) => {
+            eprintln!("{msg}");
+            1
+        }
+    }
+}
+
+fn download(ver: &str) -> i32 {
+    let (tx, rx) = unbounded();
+    let ver_owned = ver.to_string();
+    let worker = thread::spawn(move || {
+        let cancel = AtomicBool::new(false);
+        let mut emit = |evt| {
+            let _ = tx.send(evt);
+        };
+        if let Err(e) = versions::download_and_extract(&ver_owned, &mut emit, &cancel) {
+            let _ = tx.send(ProgressEvent::Error(e.to_string()));
+        }
+    });
+
+    let mut code = 1;
+    for evt in rx {
+        match evt {
+            ProgressEvent::Progress(f) => {
+                eprint!("\rDownloading v{ver}… {:>3.0}%", f * 100.0);
+                let _ = io::stderr().flush();
+            }
+            ProgressEvent::Bytes(got) => {
+                eprint!("\rDownloading v{ver}… {}", disk::human_bytes(got));
+                let _ = io::stderr().flush();
+            }
+            ProgressEvent::Transfer { .. } => {}
+            ProgressEvent::Unverified => eprintln!("\nNo checksum is published for v{ver}; installing unverified"),
+            ProgressEvent::Retrying(n) => eprintln!("\nConnection failed, retry {n} of {}…", net::RETRIES),
+            ProgressEvent::Finished => {
+                eprintln!("\rv{ver} downloaded & extracted");
+                code = 0;
+            }
+            ProgressEvent::Error(e) => eprintln!("\nError: {e}"),
+            ProgressEvent::Cancelled => eprintln!("\nCancelled"),
+        }
+    }
+    let _ = worker.join();
+    code
+}
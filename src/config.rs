@@ -0,0 +1,137 @@
+//! config.rs – user preferences, persisted as `settings.json`
+use std::{fs, io, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::paths;
+
+/// Official game-file CDN; mirrors are expected to copy its layout.
+pub const DEFAULT_CDN: &str = "https://cdn.vintagestory.at/gamefiles/";
+
+/// What closing the launcher does to games it started that still run.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum OnExit {
+    /// detach and keep playing
+    Leave,
+    /// ask each game to quit, kill it if it doesn't
+    Stop,
+    /// decide in a dialog every time
+    #[default]
+    Ask,
+}
+
+/// Colour scheme of the launcher window.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    /// follow the OS; dark when it can't be told
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+impl Theme {
+    /// Whether to draw dark. `System` asks the OS, so call it on change,
+    /// not every frame.
+    pub fn is_dark(self) -> bool {
+        match self {
+            Theme::System => dark_light::detect() != dark_light::Mode::Light,
+            Theme::Light => false,
+            Theme::Dark => true,
+        }
+    }
+}
+
+/// Everything the Settings page lets the user change. Missing keys fall
+/// back to their defaults, so old files keep loading.
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct Config {
+    /// mods requested per API page (first open and every "load more")
+    pub mods_page_size: usize,
+    /// fetch the mod list as soon as the Mods page opens
+    pub mods_auto_load: bool,
+    /// Mods page as a dense one-row-per-mod list instead of the card grid
+    pub mods_compact: bool,
+    /// base URL replacing `DEFAULT_CDN` for game downloads; empty = official
+    pub cdn_mirror: String,
+    /// parent folder for new instances' data; empty = `paths::instances_dir()`
+    pub instances_root: String,
+    /// download speed cap in KB/s; 0 = unlimited
+    pub download_limit_kbps: u32,
+    /// game versions downloading at once; more wait in a queue
+    pub parallel_downloads: usize,
+    /// closing the launcher while its games run
+    pub on_exit: OnExit,
+    pub theme: Theme,
+    /// refuse to launch an instance whose mods have unmet dependencies
+    /// (otherwise its card just warns)
+    pub block_missing_deps: bool,
+    /// folder for versions, instances and caches; empty = `paths::default_base_dir()`.
+    /// Read once at startup.
+    pub data_dir: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            mods_page_size: 96,
+            mods_auto_load: true,
+            mods_compact: false,
+            cdn_mirror: String::new(),
+            instances_root: String::new(),
+            download_limit_kbps: 0,
+            parallel_downloads: 2,
+            on_exit: OnExit::default(),
+            theme: Theme::default(),
+            block_missing_deps: false,
+            data_dir: String::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Download base in use, always ending in `/`.
+    pub fn cdn_base(&self) -> String {
+        let mirror = self.cdn_mirror.trim();
+        if mirror.is_empty() {
+            return DEFAULT_CDN.into();
+        }
+        format!("{}/", mirror.trim_end_matches('/'))
+    }
+
+    /// Custom parent for new instances, if one is set (absolute paths only).
+    pub fn instances_root(&self) -> Option<PathBuf> {
+        Some(PathBuf::from(self.instances_root.trim())).filter(|p| p.is_absolute())
+    }
+
+    /// Custom data folder, if one is set (absolute paths only).
+    pub fn data_dir(&self) -> Option<PathBuf> {
+        Some(PathBuf::from(self.data_dir.trim())).filter(|p| p.is_absolute())
+    }
+
+    /// Speed cap in bytes per second, if one is set.
+    pub fn download_limit(&self) -> Option<u64> {
+        (self.download_limit_kbps > 0).then(|| u64::from(self.download_limit_kbps) * 1000)
+    }
+
+    /// Download slots, never fewer than one.
+    pub fn parallel_downloads(&self) -> usize {
+        self.parallel_downloads.max(1)
+    }
+
+    pub fn load() -> Self {
+        fs::read_to_string(paths::settings_file())
+            .ok()
+            .and_then(|txt| serde_json::from_str(&txt).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let path = paths::settings_file();
+        if let Some(p) = path.parent() { fs::create_dir_all(p)?; }
+        fs::write(path, serde_json::to_string_pretty(self).map_err(io::Error::other)?)
+    }
+}
@@ -0,0 +1,129 @@
+//! disk.rs – small filesystem helpers shared by the pages
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::activity::{Activity, TaskId};
+
+/// Free bytes on the volume holding `path`. Walks up to the nearest
+/// existing ancestor so it also works before the folder is created.
+pub fn free_space(path: &Path) -> Option<u64> {
+    let existing = path.ancestors().find(|p| p.exists())?;
+    fs2::available_space(existing).ok()
+}
+
+/// "23.4 GB", "512.0 MB", … (decimal units, like file managers show)
+pub fn human_bytes(n: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut v = n as f64;
+    let mut unit = 0;
+    while v >= 1000.0 && unit < UNITS.len() - 1 {
+        v /= 1000.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{n} B")
+    } else {
+        format!("{v:.1} {}", UNITS[unit])
+    }
+}
+
+/// Total size of the files under `path` (0 if missing). Symlinks are
+/// counted as themselves, not followed.
+pub fn dir_size(path: &Path) -> u64 {
+    let Ok(meta) = fs::symlink_metadata(path) else { return 0 };
+    if !meta.is_dir() {
+        return meta.len();
+    }
+    fs::read_dir(path)
+        .map(|rd| rd.flatten().map(|e| dir_size(&e.path())).sum())
+        .unwrap_or(0)
+}
+
+/// Copies a file, or a folder with everything below it, to `dst`.
+pub fn copy_recursive(src: &Path, dst: &Path) -> io::Result<()> {
+    if src.is_dir() {
+        fs::create_dir_all(dst)?;
+        for entry in fs::read_dir(src)? {
+            let entry = entry?;
+            copy_recursive(&entry.path(), &dst.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        if let Some(p) = dst.parent() { fs::create_dir_all(p)?; }
+        fs::copy(src, dst).map(|_| ())
+    }
+}
+
+/// `remove_dir_all` on a worker thread; the task posts one `io::Result<()>`.
+/// A folder that is already gone counts as success.
+pub fn remove_dir_in_background(path: PathBuf, activity: &Activity) -> TaskId {
+    remove_dirs_in_background(vec![path.clone()], format!("Deleting {}", path.display()), activity)
+}
+
+/// `remove_dir_in_background` for several folders, in order; stops at the
+/// first error.
+pub fn remove_dirs_in_background(paths: Vec<PathBuf>, label: String, activity: &Activity) -> TaskId {
+    activity.spawn(label, move |task| {
+        let res = paths.iter().try_for_each(|path| match fs::remove_dir_all(path) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            other => other,
+        });
+        task.post(res);
+    })
+}
+
+/// `copy_recursive` for each `(src, dst)` pair on a worker thread, in
+/// order; stops at the first error. Missing sources are skipped. The task
+/// posts one `io::Result<()>`.
+pub fn copy_in_background(pairs: Vec<(PathBuf, PathBuf)>, label: String, activity: &Activity) -> TaskId {
+    activity.spawn(label, move |task| {
+        let res = pairs
+            .iter()
+            .filter(|(src, _)| src.exists())
+            .try_for_each(|(src, dst)| copy_recursive(src, dst));
+        task.post(res);
+    })
+}
+
+/// Opens the file manager with `path` selected where the platform supports it;
+/// otherwise opens the containing folder.
+pub fn reveal_file(path: &Path) -> io::Result<()> {
+    use std::process::Command;
+
+    let parent = path.parent().unwrap_or(path);
+    if cfg!(target_os = "windows") {
+        let mut select = std::ffi::OsString::from("/select,");
+        select.push(path);
+        Command::new("explorer").arg(select).spawn().map(|_| ())
+    } else if cfg!(target_os = "macos") {
+        Command::new("open").arg("-R").arg(path).spawn().map(|_| ())
+    } else {
+        // freedesktop FileManager1 selects the item in Nautilus, Dolphin, Nemo, …
+        let Ok(uri) = reqwest::Url::from_file_path(path) else { return open::that(parent) };
+        // dbus-send splits array items on commas, which a URI leaves as-is
+        let item = format!("array:string:{}", uri.as_str().replace(',', "%2C"));
+        let send = Command::new("dbus-send")
+            .args([
+                "--session",
+                "--dest=org.freedesktop.FileManager1",
+                "--type=method_call",
+                "/org/freedesktop/FileManager1",
+                "org.freedesktop.FileManager1.ShowItems",
+            ])
+            .arg(item)
+            .arg("string:")
+            .spawn();
+        let Ok(mut send) = send else { return open::that(parent) };
+        // the bus can take a while to answer (or to say nobody listens); wait
+        // off the UI thread and fall back to the folder if nothing showed it
+        let parent = parent.to_path_buf();
+        std::thread::spawn(move || {
+            if !send.wait().is_ok_and(|s| s.success()) {
+                let _ = open::that(parent);
+            }
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,241 @@
+//! extract.rs – unpack game archives without letting entries escape the target
+//!
+//! libarchive streams from the file, so nothing is buffered whole. compress-tools
+//! already strips `..` and leading `/` from entry names, but symlinks are written
+//! as-is and later entries are happily written *through* them. We refuse such
+//! archives up front and re-check every link once unpacked.
+use std::{
+    cell::RefCell,
+    fs, io,
+    path::{Component, Path, PathBuf},
+    rc::Rc,
+};
+
+use compress_tools::{uncompress_archive, ArchiveContents, ArchiveIteratorBuilder, Ownership};
+
+/// Extracts `archive` into `dest`, replacing whatever `dest` held. Files get
+/// the current user's ownership, not the uids recorded in the archive. The
+/// archive is unpacked and checked in a sibling folder first and only then
+/// moved into place, so a bad archive leaves an existing `dest` untouched.
+pub fn extract_archive(archive: &Path, dest: &Path) -> io::Result<()> {
+    check_entries(archive)?;
+    let staging = sibling(dest, "extracting");
+    let _ = fs::remove_dir_all(&staging);
+    fs::create_dir_all(&staging)?;
+    let unpacked = fs::File::open(archive)
+        .and_then(|f| uncompress_archive(f, &staging, Ownership::Ignore).map_err(io::Error::other))
+        .and_then(|()| check_symlinks(&staging, &staging));
+    if let Err(e) = unpacked {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+    replace_dir(&staging, dest)
+}
+
+/// `<dest>.<suffix>` next to `dest`.
+fn sibling(dest: &Path, suffix: &str) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{suffix}"));
+    dest.with_file_name(name)
+}
+
+/// Moves `new` to `dest`; an existing `dest` is set aside first and put
+/// back if the move fails.
+fn replace_dir(new: &Path, dest: &Path) -> io::Result<()> {
+    if !dest.exists() {
+        return fs::rename(new, dest);
+    }
+    let old = sibling(dest, "replaced");
+    let _ = fs::remove_dir_all(&old);
+    fs::rename(dest, &old)?;
+    if let Err(e) = fs::rename(new, dest) {
+        let _ = fs::rename(&old, dest);
+        let _ = fs::remove_dir_all(new);
+        return Err(e);
+    }
+    let _ = fs::remove_dir_all(&old);
+    Ok(())
+}
+
+fn unsafe_archive(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("unsafe archive: {msg}"))
+}
+
+/// Lists the entries (without decompressing their data) and rejects
+/// escaping names and entries nested under a symlink entry.
+fn check_entries(archive: &Path) -> io::Result<()> {
+    let seen: Rc<RefCell<Vec<(PathBuf, bool)>>> = Rc::default();
+    let sink = seen.clone();
+    let iter = ArchiveIteratorBuilder::new(fs::File::open(archive)?)
+        .filter(move |name, st| {
+            let is_link = st.st_mode & 0o170000 == 0o120000;
+            sink.borrow_mut().push((PathBuf::from(name), is_link));
+            false // names are enough; skip the data
+        })
+        .build()
+        .map_err(io::Error::other)?;
+    for item in iter {
+        if let ArchiveContents::Err(e) = item {
+            return Err(io::Error::other(e));
+        }
+    }
+
+    let entries = seen.borrow();
+    let links: Vec<PathBuf> = entries
+        .iter()
+        .filter(|(_, is_link)| *is_link)
+        .map(|(p, _)| normalize(p))
+        .collect();
+    for (name, _) in entries.iter() {
+        if name.is_absolute() || name.components().any(|c| c == Component::ParentDir) {
+            return Err(unsafe_archive(format!("entry {} escapes the target", name.display())));
+        }
+        let name = normalize(name);
+        if let Some(link) = links.iter().find(|l| name != **l && name.starts_with(l)) {
+            return Err(unsafe_archive(format!(
+                "{} is written through symlink {}",
+                name.display(),
+                link.display()
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Drops `.` components so `./a/b` and `a/b` compare equal.
+fn normalize(p: &Path) -> PathBuf {
+    p.components().filter(|c| *c != Component::CurDir).collect()
+}
+
+/// Every symlink below `dir` must resolve (lexically) to somewhere inside `root`.
+fn check_symlinks(root: &Path, dir: &Path) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let meta = fs::symlink_metadata(&path)?;
+        if meta.file_type().is_symlink() {
+            let target = fs::read_link(&path)?;
+            let parent = path.parent().unwrap_or(root);
+            if !stays_inside(root, &parent.join(&target)) {
+                return Err(unsafe_archive(format!(
+                    "symlink {} points outside ({})",
+                    path.display(),
+                    target.display()
+                )));
+            }
+        } else if meta.is_dir() {
+            check_symlinks(root, &path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Resolves `..` without touching the filesystem and checks the result is under `root`.
+fn stays_inside(root: &Path, path: &Path) -> bool {
+    let mut out = PathBuf::new();
+    for c in path.components() {
+        match c {
+            Component::ParentDir => {
+                if !out.pop() {
+                    return false;
+                }
+            }
+            Component::CurDir => {}
+            other => out.push(other),
+        }
+    }
+    out.starts_with(root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One ustar entry: `kind` b'0' for a file (with `body`), b'2' for a
+    /// symlink to `link`.
+    fn tar_entry(name: &str, kind: u8, link: &str, body: &[u8]) -> Vec<u8> {
+        let mut head = [0u8; 512];
+        let mut put = |at: usize, bytes: &[u8]| head[at..at + bytes.len()].copy_from_slice(bytes);
+        put(0, name.as_bytes());
+        put(100, b"0000644\0");
+        put(108, b"0000000\0");
+        put(116, b"0000000\0");
+        put(124, format!("{:011o}\0", body.len()).as_bytes());
+        put(136, b"00000000000\0");
+        put(148, b"        ");
+        put(156, &[kind]);
+        put(157, link.as_bytes());
+        put(257, b"ustar\x0000");
+        let sum: u32 = head.iter().map(|&b| b as u32).sum();
+        head[148..156].copy_from_slice(format!("{sum:06o}\0 ").as_bytes());
+
+        let mut out = head.to_vec();
+        out.extend_from_slice(body);
+        out.resize(out.len().div_ceil(512) * 512, 0);
+        out
+    }
+
+    /// Writes `entries` as a tar next to `dir` and returns its path.
+    fn write_tar(dir: &Path, entries: &[Vec<u8>]) -> PathBuf {
+        let mut tar = entries.concat();
+        tar.resize(tar.len() + 1024, 0); // end-of-archive marker
+        let path = dir.join("archive.tar");
+        fs::write(&path, tar).unwrap();
+        path
+    }
+
+    #[test]
+    fn the_fixture_unpacks_intact() {
+        let tmp = tempfile::tempdir().unwrap();
+        let archive = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/vs_client_tiny.tar.gz");
+        let dest = tmp.path().join("install");
+        extract_archive(&archive, &dest).unwrap();
+        assert!(dest.join("vintagestory/Vintagestory").is_file());
+        assert_eq!(fs::read_to_string(dest.join("vintagestory/assets/version.txt")).unwrap().trim(), "1.0.0-test");
+    }
+
+    #[test]
+    fn escaping_entries_are_refused_before_anything_is_written() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dest = tmp.path().join("install");
+        let archive = write_tar(tmp.path(), &[
+            tar_entry("ok.txt", b'0', "", b"fine"),
+            tar_entry("../evil.txt", b'0', "", b"gotcha"),
+        ]);
+        let err = extract_archive(&archive, &dest).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("../evil.txt escapes the target"), "{err}");
+        assert!(!dest.exists());
+        assert!(!tmp.path().join("evil.txt").exists());
+    }
+
+    #[test]
+    fn a_bad_archive_leaves_the_previous_install_alone() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dest = tmp.path().join("install");
+        fs::create_dir_all(&dest).unwrap();
+        fs::write(dest.join("Vintagestory"), b"old build").unwrap();
+        let archive = write_tar(tmp.path(), &[tar_entry("escape", b'2', "../../outside", b"")]);
+        let err = extract_archive(&archive, &dest).unwrap_err();
+        assert!(err.to_string().contains("points outside"), "{err}");
+        assert_eq!(fs::read(dest.join("Vintagestory")).unwrap(), b"old build");
+        assert!(!tmp.path().join("install.extracting").exists());
+
+        // a good one replaces it whole
+        let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/vs_client_tiny.tar.gz");
+        extract_archive(&fixture, &dest).unwrap();
+        assert!(!dest.join("Vintagestory").exists());
+        assert!(dest.join("vintagestory/Vintagestory").is_file());
+        assert!(!tmp.path().join("install.replaced").exists());
+    }
+
+    #[test]
+    fn entries_written_through_a_symlink_are_refused() {
+        let tmp = tempfile::tempdir().unwrap();
+        let archive = write_tar(tmp.path(), &[
+            tar_entry("data", b'2', "/tmp", b""),
+            tar_entry("data/planted.txt", b'0', "", b"gotcha"),
+        ]);
+        let err = extract_archive(&archive, &tmp.path().join("install")).unwrap_err();
+        assert!(err.to_string().contains("written through symlink data"), "{err}");
+    }
+}
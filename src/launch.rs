@@ -0,0 +1,161 @@
+//! launch.rs – start the game for an instance (shared by the GUI and the CLI)
+use std::{
+    ffi::OsString,
+    io,
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+};
+
+use crate::{pages::instances::Instance, paths};
+
+/// Client flag that loads a save on startup; takes the save's path.
+const OPEN_WORLD_ARG: &str = "--openWorld";
+
+/// How to start the game.
+#[derive(Clone, Default)]
+pub struct LaunchOptions {
+    /// pipe the child's stdout and stderr back to the launcher
+    pub capture: bool,
+    /// `.vcdbs` save to open instead of the main menu
+    pub world:   Option<PathBuf>,
+}
+
+/// The game binary inside an extracted client folder (`<install>/vintagestory`),
+/// in the shape this platform's client ships it.
+pub fn find_executable(root: &Path) -> Option<PathBuf> {
+    let candidates: &[&str] = if cfg!(target_os = "windows") {
+        &["Vintagestory.exe"]
+    } else if cfg!(target_os = "macos") {
+        &["Vintagestory.app", "Vintagestory"]
+    } else {
+        &["Vintagestory", "run.sh"]
+    };
+    candidates.iter().map(|c| root.join(c)).find(|p| p.exists())
+}
+
+/// The command that starts `bin`. App bundles go through `open`, told to
+/// wait so the launcher still sees when the game exits; game arguments go
+/// after `--args`. Anything else runs directly.
+fn command_for(bin: &Path) -> Command {
+    if cfg!(target_os = "macos") && bin.extension().is_some_and(|e| e == "app") {
+        let mut cmd = Command::new("open");
+        cmd.args(["-W", "-n"]).arg(bin).arg("--args");
+        cmd
+    } else {
+        Command::new(bin)
+    }
+}
+
+/// Sets the executable bits if an extract or copy lost them.
+#[cfg(unix)]
+fn ensure_executable(bin: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+
+    if let Some(meta) = std::fs::metadata(bin).ok().filter(|m| m.is_file()) {
+        let mut perms = meta.permissions();
+        if perms.mode() & 0o111 == 0 {
+            perms.set_mode(perms.mode() | 0o755);
+            let _ = std::fs::set_permissions(bin, perms);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn ensure_executable(_bin: &Path) {}
+
+/// Binary to run for `inst` and the directory to run it in: the instance's
+/// custom executable (from its own folder), else the managed install's.
+pub fn executable(inst: &Instance) -> Result<(PathBuf, PathBuf), String> {
+    if let Some(bin) = &inst.custom_executable {
+        if !bin.is_file() {
+            return Err(format!("Custom executable {} not found", bin.display()));
+        }
+        let dir = bin.parent().map(Path::to_path_buf).unwrap_or_default();
+        return Ok((bin.clone(), dir));
+    }
+    let root = paths::install_dir(&inst.version).join("vintagestory");
+    match find_executable(&root) {
+        Some(bin) => Ok((bin, root)),
+        None => Err(format!("Executable not found for {}", inst.name)),
+    }
+}
+
+/// Game arguments for `inst`: its own data and mods folders, then the
+/// options. One entry per argument; nothing goes through a shell, so paths
+/// with spaces need no quoting.
+pub fn game_args(inst: &Instance, opts: &LaunchOptions) -> Vec<OsString> {
+    let mut args: Vec<OsString> = vec![
+        "--dataPath".into(),
+        inst.data_dir().into(),
+        "--addModPath".into(),
+        inst.mods_dir().into(),
+    ];
+    if let Some(world) = &opts.world {
+        args.push(OPEN_WORLD_ARG.into());
+        args.push(world.into());
+    }
+    args
+}
+
+/// Spawns the game for `inst`. The error is a user-facing message.
+pub fn launch(inst: &Instance, opts: LaunchOptions) -> Result<Child, String> {
+    let (bin, root) = executable(inst)?;
+    ensure_executable(&bin);
+
+    // no shell in between
+    let mut cmd = command_for(&bin);
+    cmd.args(game_args(inst, &opts));
+    let pipe = || if opts.capture { Stdio::piped() } else { Stdio::inherit() };
+    cmd.current_dir(&root)
+        .stdout(pipe())
+        .stderr(pipe())
+        .spawn()
+        .map_err(|e| {
+            eprintln!("launch failed: {e}");
+            if e.kind() == io::ErrorKind::NotFound {
+                // the file exists, so this is a missing interpreter (e.g. a run.sh shebang)
+                format!(
+                    "Cannot launch {}: {} or its interpreter was not found",
+                    inst.name,
+                    bin.display()
+                )
+            } else {
+                format!("Launch error: {e}")
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::instance;
+
+    #[test]
+    fn args_point_the_game_at_the_instance_folders() {
+        let tmp = tempfile::tempdir().unwrap();
+        let data = tmp.path().join("My Worlds");
+        let inst = instance(&data);
+        let args = game_args(&inst, &LaunchOptions::default());
+        assert_eq!(
+            args,
+            vec![
+                OsString::from("--dataPath"),
+                data.clone().into(),
+                "--addModPath".into(),
+                inst.mods_dir().into(),
+            ]
+        );
+        // the space stays inside one argument
+        assert!(args[1].to_string_lossy().ends_with("My Worlds"));
+    }
+
+    #[test]
+    fn options_follow_the_folders() {
+        let tmp = tempfile::tempdir().unwrap();
+        let inst = instance(tmp.path());
+        let world = tmp.path().join("Saves/Home.vcdbs");
+        let opts = LaunchOptions { world: Some(world.clone()), ..LaunchOptions::default() };
+        let args = game_args(&inst, &opts);
+        assert_eq!(args[4..], [OsString::from(OPEN_WORLD_ARG), world.into()]);
+    }
+}
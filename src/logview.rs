@@ -0,0 +1,177 @@
+//! logview.rs – live window over a running game's stdout/stderr, and the
+//! output of the last few launches
+use std::{collections::VecDeque, sync::Arc};
+
+use eframe::egui;
+
+use crate::{modhistory, process::LogBuffer};
+
+/// How many launches' logs stay reachable from the log window.
+pub const RECENT_LAUNCHES: usize = 5;
+
+/// Output of one launch, kept after the game exits.
+#[derive(Clone)]
+pub struct RecentLog {
+    /// instance that was launched
+    pub id:      u64,
+    pub title:   String,
+    /// unix seconds
+    pub started: u64,
+    pub log:     LogBuffer,
+}
+
+impl RecentLog {
+    fn label(&self) -> String {
+        format!("{} – {}", self.title, modhistory::format_time(self.started))
+    }
+}
+
+/// Adds `entry` as the newest launch, forgetting the oldest beyond `RECENT_LAUNCHES`.
+pub fn remember(recent: &mut VecDeque<RecentLog>, entry: RecentLog) {
+    recent.push_front(entry);
+    recent.truncate(RECENT_LAUNCHES);
+}
+
+/// Lines worth stopping at when reading a startup log.
+pub fn is_error(line: &str) -> bool {
+    line.contains("Exception") || line.to_ascii_uppercase().contains("ERROR")
+}
+
+/// Index of the first error line after `from` (wrapping around), if any.
+pub fn next_error(lines: &[String], from: Option<usize>) -> Option<usize> {
+    let start = from.map_or(0, |i| i + 1);
+    (start..lines.len()).chain(0..start.min(lines.len())).find(|&i| is_error(&lines[i]))
+}
+
+pub struct LogView {
+    /// launch being shown
+    entry:  RecentLog,
+    /// keep the newest line in view
+    follow: bool,
+    /// error line picked by "Next error"
+    cursor: Option<usize>,
+    /// scroll to `cursor` on the next frame
+    jump:   bool,
+}
+
+impl LogView {
+    pub fn new(entry: RecentLog) -> Self {
+        Self { entry, follow: true, cursor: None, jump: false }
+    }
+
+    /// instance the shown log belongs to
+    pub fn id(&self) -> u64 {
+        self.entry.id
+    }
+
+    /// Whether this is the log `buffer` is writing to.
+    pub fn shows(&self, buffer: &LogBuffer) -> bool {
+        Arc::ptr_eq(&self.entry.log, buffer)
+    }
+
+    /// Draws the window; `running` only changes the heading. `recent` fills
+    /// the launch picker. Returns `false` once the user closed it.
+    pub fn ui(&mut self, ctx: &egui::Context, running: bool, recent: &VecDeque<RecentLog>) -> bool {
+        let lines: Vec<String> = self.entry.log.lock().unwrap().iter().cloned().collect();
+        let errors = lines.iter().filter(|l| is_error(l)).count();
+        let mut open = true;
+        let state = if running { "running" } else { "exited" };
+        egui::Window::new(format!("{} – log ({state})", self.entry.title))
+            .id(egui::Id::new("game_log"))
+            .open(&mut open)
+            .default_size([720.0, 420.0])
+            .show(ctx, |ui| {
+                if recent.len() > 1 {
+                    let mut picked = None;
+                    egui::ComboBox::from_id_source("game_log_launch")
+                        .selected_text(self.entry.label())
+                        .width(320.0)
+                        .show_ui(ui, |ui| {
+                            for r in recent {
+                                if ui.selectable_label(self.shows(&r.log), r.label()).clicked() {
+                                    picked = Some(r.clone());
+                                }
+                            }
+                        });
+                    if let Some(entry) = picked {
+                        *self = Self::new(entry);
+                    }
+                }
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.follow, "Auto-scroll");
+                    let next = ui.add_enabled(errors > 0, egui::Button::new(format!("Next error ({errors})")));
+                    if next.on_hover_text("Lines containing \"Exception\" or \"ERROR\"").clicked() {
+                        self.cursor = next_error(&lines, self.cursor);
+                        self.jump = self.cursor.is_some();
+                        self.follow = false;
+                    }
+                    if ui.button("Copy all").clicked() {
+                        ui.output_mut(|o| o.copied_text = lines.join("\n"));
+                    }
+                });
+                ui.separator();
+                egui::ScrollArea::both()
+                    .auto_shrink([false, false])
+                    .stick_to_bottom(self.follow)
+                    .show(ui, |ui| {
+                        if lines.is_empty() {
+                            ui.weak("Nothing logged yet");
+                        }
+                        for (i, line) in lines.iter().enumerate() {
+                            let mut text = egui::RichText::new(line).monospace();
+                            if is_error(line) {
+                                text = text.color(ui.visuals().error_fg_color);
+                            }
+                            if self.cursor == Some(i) {
+                                text = text.background_color(ui.visuals().selection.bg_fill);
+                            }
+                            let resp = ui.label(text);
+                            if self.jump && self.cursor == Some(i) {
+                                resp.scroll_to_me(Some(egui::Align::Center));
+                                self.jump = false;
+                            }
+                        }
+                    });
+            });
+        if running {
+            ctx.request_repaint_after(std::time::Duration::from_millis(250));
+        }
+        open
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(raw: &[&str]) -> Vec<String> {
+        raw.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn errors_are_found_case_insensitively() {
+        assert!(is_error("12:00:01 [Error] Failed loading mod"));
+        assert!(is_error("System.NullReferenceException: boom"));
+        assert!(!is_error("12:00:01 [Notification] Loaded 4 mods"));
+    }
+
+    #[test]
+    fn only_the_last_launches_are_kept() {
+        let mut recent = VecDeque::new();
+        for id in 0..RECENT_LAUNCHES as u64 + 2 {
+            remember(&mut recent, RecentLog { id, title: String::new(), started: 0, log: LogBuffer::default() });
+        }
+        assert_eq!(recent.len(), RECENT_LAUNCHES);
+        assert_eq!(recent.front().map(|r| r.id), Some(RECENT_LAUNCHES as u64 + 1));
+        assert_eq!(recent.back().map(|r| r.id), Some(2));
+    }
+
+    #[test]
+    fn next_error_steps_forward_and_wraps() {
+        let log = lines(&["ok", "[Error] a", "ok", "Exception b", "ok"]);
+        assert_eq!(next_error(&log, None), Some(1));
+        assert_eq!(next_error(&log, Some(1)), Some(3));
+        assert_eq!(next_error(&log, Some(3)), Some(1));
+        assert_eq!(next_error(&lines(&["ok"]), None), None);
+    }
+}
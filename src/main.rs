@@ -1,20 +1,241 @@
+mod activity;
+mod cli;
+mod config;
+mod disk;
+mod extract;
+mod launch;
+mod logview;
+mod modhistory;
+mod modinfo;
+mod modlock;
+mod modupdate;
+mod net;
+mod notify;
+mod paths;
 mod pages;
+mod preflight;
+mod process;
+mod schema;
+mod shortcut;
+#[cfg(test)]
+mod testutil;
+mod textures;
+mod uistate;
+mod worlds;
+use std::{
+    collections::{HashMap, VecDeque},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use activity::{Activity, TaskId, TryRecvError};
+use config::{Config, OnExit, Theme};
 use eframe::{egui, App, Frame};
-use pages::{home::HomePage, versions::VersionPage, instances::InstancesPage, mods::ModsPage};
+use net::Connectivity;
+use notify::{Action, Notifier};
+use launch::LaunchOptions;
+use logview::{LogView, RecentLog};
+use modupdate::UpdateEvent;
+use process::GameProcess;
+use pages::{home::HomePage, versions::VersionPage, instances::InstancesPage, mods::ModsPage, settings::{SettingsCmd, SettingsPage}};
 use pages::instances::InstanceCmd;
-enum View { Home, Versions, Instances, Mods}
-pub struct VsLauncherApp { view: View, home: HomePage, versions: VersionPage, instances: InstancesPage, selected_idx: Option<usize>, mods: ModsPage}
+use uistate::{Shortcut, UiState, View};
+pub struct VsLauncherApp {
+    view: View,
+    home: HomePage,
+    versions: VersionPage,
+    instances: InstancesPage,
+    /// footer selection, by instance id
+    selected_id: Option<u64>,
+    /// filter typed inside the footer combo
+    footer_filter: String,
+    mods: ModsPage,
+    settings: SettingsPage,
+    config: Config,
+    activity: Activity,
+    notify: Notifier,
+    /// menu-bar Tasks window
+    show_tasks: bool,
+    /// games started from the GUI, by instance id
+    running: HashMap<u64, GameProcess>,
+    crash: Option<CrashReport>,
+    /// launch held back because another copy already uses the data folder
+    confirm_launch: Option<PendingLaunch>,
+    /// world launch held back because the save came from another game version
+    confirm_world: Option<(LaunchRequest, String)>,
+    /// mods being updated before a launch
+    mod_update: Option<ModUpdate>,
+    /// last successful launch, for Relaunch / F5
+    last_launch: Option<LaunchRequest>,
+    /// log window, opened by "Play & watch log" or the Logs button
+    log_view: Option<LogView>,
+    /// output of the last few launches, newest first
+    recent_logs: VecDeque<RecentLog>,
+    net: Connectivity,
+    net_probe: Option<TaskId>,
+    net_checked: Option<Instant>,
+    /// unwritable data folders found at startup; shown until dismissed
+    storage_problems: Vec<String>,
+    /// theme the visuals were last set for; `None` = not yet
+    applied_theme: Option<Theme>,
+    /// close held back to ask what happens to the running games; the flag
+    /// is the dialog's "remember" box
+    confirm_exit: Option<bool>,
+    /// the user settled that; let the next close request through
+    exit_confirmed: bool,
+}
 impl Default for VsLauncherApp {
-    fn default() -> Self { Self { view: View::Home, home: HomePage::default(), versions: VersionPage::default(), instances: InstancesPage::default(), selected_idx: None, mods: ModsPage::default()} }
+    fn default() -> Self {
+        let config = Config::load();
+        let storage_problems = preflight::storage_problems(&config);
+        let ui_state = UiState::load();
+        let instances = InstancesPage::default();
+        // the instance may have been deleted since
+        let selected_id = ui_state.selected_id.filter(|id| instances.get(*id).is_some());
+        Self {
+            view: ui_state.view,
+            home: HomePage,
+            versions: VersionPage::default(),
+            instances,
+            selected_id,
+            footer_filter: String::new(),
+            mods: ModsPage::default(),
+            settings: SettingsPage::default(),
+            config,
+            activity: Activity::default(),
+            notify: Notifier::default(),
+            show_tasks: false,
+            running: HashMap::new(),
+            crash: None,
+            confirm_launch: None,
+            confirm_world: None,
+            last_launch: None,
+            log_view: None,
+            recent_logs: VecDeque::new(),
+            mod_update: None,
+            net: Connectivity::Unknown,
+            net_probe: None,
+            net_checked: None,
+            storage_problems,
+            applied_theme: None,
+            confirm_exit: None,
+            exit_confirmed: false,
+        }
+    }
+}
+
+/// One "start this instance" request, carried through the pre-launch steps.
+#[derive(Clone)]
+struct LaunchRequest {
+    id:      u64,
+    /// save to open straight away instead of the main menu
+    world:   Option<PathBuf>,
+    /// open the live log window once the game is up
+    watch_log: bool,
+}
+
+impl LaunchRequest {
+    fn new(id: u64) -> Self {
+        Self { id, world: None, watch_log: false }
+    }
+}
+
+/// Auto-update run that holds back a launch until it finishes.
+struct ModUpdate {
+    req:      LaunchRequest,
+    task:     TaskId,
+    cancel:   Arc<AtomicBool>,
+    current:  String,
+    progress: Option<f32>,
+    /// "updated …" / "failed …" lines so far
+    log:      Vec<String>,
+    updated:  usize,
+}
+
+struct PendingLaunch {
+    req: LaunchRequest,
+    pid: u32,
+}
+
+/// What the crash dialog shows after a game exits with an error.
+struct CrashReport {
+    instance: String,
+    status:   String,
+    stderr:   Vec<String>,
+    logs_dir: PathBuf,
 }
 impl App for VsLauncherApp {
-    fn update(&mut self, ctx: &egui::Context, frame: &mut Frame) {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut Frame) {
+        if self.applied_theme != Some(self.config.theme) {
+            let dark = self.config.theme.is_dark();
+            ctx.set_visuals(if dark { egui::Visuals::dark() } else { egui::Visuals::light() });
+            self.applied_theme = Some(self.config.theme);
+        }
+        let enter_taken = match self.view {
+            View::Instances => self.instances.consumes_enter(),
+            View::Versions => self.versions.consumes_enter(),
+            _ => false,
+        };
+        match uistate::shortcut(ctx, enter_taken) {
+            Some(Shortcut::Go(view)) => self.view = view,
+            Some(Shortcut::Play) => {
+                if let Some(id) = self.selected_id.filter(|id| self.instances.get(*id).is_some()) {
+                    self.launch_instance(LaunchRequest::new(id));
+                }
+            }
+            None => {}
+        }
+        self.poll_games(ctx);
+        if ctx.input(|i| i.viewport().close_requested()) {
+            self.close_requested(ctx);
+        }
+        self.poll_connectivity(ctx);
+        self.versions.poll(ctx, &self.activity, &self.notify, &self.config);
+        if ctx.input(|i| i.key_pressed(egui::Key::F5)) {
+            if let Some(req) = self.last_launch.clone() {
+                self.launch_instance(req);
+            }
+        }
         eframe::egui::TopBottomPanel::top("menu").show(ctx, |ui| {
             ui.horizontal(|ui| {
-                if ui.button("Home").clicked()     { self.view = View::Home; }
-                if ui.button("Versions").clicked() { self.view = View::Versions; }
-                if ui.button("Instances").clicked() { self.view = View::Instances; }
-                if ui.button("Mods").clicked() { self.view = View::Mods; }
+                for (view, label) in View::ALL.into_iter().zip(["Home", "Versions", "Instances", "Mods", "Settings"]) {
+                    ui.selectable_value(&mut self.view, view, label).on_hover_text(view.shortcut_text(ctx));
+                }
+
+                let busy = self.activity.count();
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    match self.net {
+                        Connectivity::Online => ui.label("● Online"),
+                        Connectivity::Offline => ui
+                            .colored_label(ui.visuals().warn_fg_color, "○ Offline")
+                            .on_hover_text("mods.vintagestory.at is unreachable"),
+                        Connectivity::Unknown => ui.weak("… checking"),
+                    };
+                    let tasks = ui
+                        .selectable_label(self.show_tasks, format!("Tasks ({busy})"))
+                        .on_hover_text("Downloads and other background work");
+                    if tasks.clicked() {
+                        self.show_tasks = !self.show_tasks;
+                    }
+                    if busy > 0 {
+                        ui.spinner();
+                    }
+                    if let Some(newest) = self.recent_logs.front() {
+                        let logs = ui
+                            .selectable_label(self.log_view.is_some(), "📜 Logs")
+                            .on_hover_text("Output of the games launched this session");
+                        if logs.clicked() {
+                            self.log_view = match self.log_view {
+                                Some(_) => None,
+                                None => Some(LogView::new(newest.clone())),
+                            };
+                        }
+                    }
+                });
             });
         });
         // run the current page and capture play-command if any
@@ -24,96 +245,592 @@ impl App for VsLauncherApp {
                 InstanceCmd::None
             }
             View::Versions => {
-                self.versions.ui(ctx);
+                self.versions.ui(ctx, &self.activity, &self.notify, &self.config, self.net, &self.instances);
                 InstanceCmd::None
             }
-            View::Instances => self.instances.ui(ctx),     // returns InstanceCmd
+            View::Instances => {
+                // the create dialog offers versions that still need downloading
+                self.versions.load_list(&self.activity, self.net);
+                self.instances.ui(ctx, &self.activity, &self.notify, &self.versions) // returns InstanceCmd
+            }
             View::Mods => {
-                self.mods.ui(ctx);
+                let selected = self.selected_id.and_then(|id| self.instances.get(id));
+                self.mods.ui(ctx, &self.activity, &self.notify, self.net, &mut self.config, &self.instances, selected);
+                for id in self.mods.take_changed() {
+                    self.instances.mods_changed(id);
+                }
+                InstanceCmd::None
+            }
+            View::Settings => {
+                if let SettingsCmd::Redownload(ver) = self.settings.ui(ctx, &self.activity, &self.notify, &mut self.config) {
+                    self.versions.redownload(ver, &self.activity, &self.config);
+                    self.view = View::Versions;
+                }
                 InstanceCmd::None
             }
         };
 
         // handle the request after the borrow on self.instances is over
-        if let InstanceCmd::Play(idx) = cmd {
-            self.launch_instance(idx);
+        match cmd {
+            InstanceCmd::Play(id) => self.launch_instance(LaunchRequest::new(id)),
+            InstanceCmd::PlayWorld { id, world } => {
+                self.launch_instance(LaunchRequest { world: Some(world), ..LaunchRequest::new(id) })
+            }
+            InstanceCmd::PlayWatchLog(id) => {
+                self.launch_instance(LaunchRequest { watch_log: true, ..LaunchRequest::new(id) })
+            }
+            InstanceCmd::ShowLog(id) => match self.recent_logs.iter().find(|r| r.id == id) {
+                Some(entry) => self.log_view = Some(LogView::new(entry.clone())),
+                None => self.notify.error("No log yet: it is kept for games launched this session"),
+            },
+            InstanceCmd::Download(ver) => self.versions.download(ver, &self.activity, &self.config),
+            InstanceCmd::None => {}
+        }
+        self.tasks_window(ctx);
+        self.log_window(ctx);
+        match self.notify.ui(ctx) {
+            Some(Action::RetryDownload(ver)) => {
+                self.versions.retry_download(ver, &self.activity, &self.config);
+                self.view = View::Versions;
+            }
+            None => {}
         }
         eframe::egui::TopBottomPanel::bottom("global_footer").show(ctx, |ui| {
             ui.horizontal(|ui| {
                 egui::ComboBox::from_id_source("global_instance_select")
                     .selected_text(
-                        self.selected_idx
-                            .and_then(|i| self.instances.instances.get(i))
-                            .map(|inst| inst.name.clone())
+                        self.selected_id
+                            .and_then(|id| self.instances.get(id))
+                            .map(|inst| {
+                                let live = if self.running.contains_key(&inst.id) { " ●" } else { "" };
+                                format!("{}{live}", inst.name)
+                            })
                             .unwrap_or_else(|| "(choose instance)".into()),
                     )
                     .show_ui(ui, |ui| {
-                        for (idx, inst) in self.instances.instances.iter().enumerate() {
-                            ui.selectable_value(&mut self.selected_idx, Some(idx), &inst.name);
+                        let filter = ui.add(
+                            egui::TextEdit::singleline(&mut self.footer_filter).hint_text("filter…"),
+                        );
+                        if !filter.has_focus() && ui.memory(|m| m.focused().is_none()) {
+                            filter.request_focus();
+                        }
+                        let shown = self.instances.ordered(&self.footer_filter);
+                        if shown.is_empty() {
+                            ui.weak("no match");
+                        }
+                        for inst in shown.iter().map(|&i| &self.instances.instances[i]) {
+                            ui.horizontal(|ui| {
+                                let tex = inst.icon.as_deref().and_then(|p| self.instances.icons.file(ctx, p));
+                                textures::avatar(ui, tex, &inst.name, 18.0);
+                                let label = if inst.favorite { format!("★ {}", inst.name) } else { inst.name.clone() };
+                                ui.selectable_value(&mut self.selected_id, Some(inst.id), label);
+                                if self.running.contains_key(&inst.id) {
+                                    ui.colored_label(egui::Color32::from_rgb(0x4c, 0xaf, 0x50), "● running");
+                                } else {
+                                    ui.weak("○ stopped");
+                                }
+                            });
                         }
                     });
 
-                let play_enabled = self.selected_idx.is_some();
-                if ui.add_enabled(play_enabled, egui::Button::new("Play")).clicked() {
-                    if let Some(idx) = self.selected_idx {
-                        self.launch_instance(idx);
+                let play_enabled = self.selected_id.is_some_and(|id| self.instances.get(id).is_some());
+                let play = ui
+                    .add_enabled(play_enabled, egui::Button::new("Play"))
+                    .on_hover_text("Enter");
+                if play.clicked() {
+                    if let Some(id) = self.selected_id {
+                        self.launch_instance(LaunchRequest::new(id));
+                    }
+                }
+
+                let last = self
+                    .last_launch
+                    .clone()
+                    .and_then(|req| self.instances.get(req.id).map(|i| (i.name.clone(), req)));
+                if let Some((name, req)) = last {
+                    let id = req.id;
+                    ui.separator();
+                    if self.running.contains_key(&id) {
+                        if ui
+                            .button(format!("⟲ Stop & relaunch {name}"))
+                            .on_hover_text("Kills the running game first")
+                            .clicked()
+                        {
+                            if let Some(mut game) = self.running.remove(&id) {
+                                game.kill();
+                            }
+                            self.launch_instance(req);
+                        }
+                    } else if ui.button(format!("⟲ Relaunch {name}")).on_hover_text("F5").clicked() {
+                        self.launch_instance(req);
                     }
                 }
             });
         });
     }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        let state = UiState { view: self.view, selected_id: self.selected_id };
+        if let Err(e) = state.save() {
+            eprintln!("could not save ui_state.json: {e}");
+        }
+    }
 }
 
 impl VsLauncherApp {
-    fn launch_instance(&mut self, idx: usize) {
-        use std::os::unix::fs::PermissionsExt;
-        if let Some(inst) = self.instances.instances.get(idx) {
-            let root = pages::versions::VersionPage::install_dir(&inst.version).join("vintagestory");
-            let candidates = [
-                root.join("Vintagestory"),
-                root.join("run.sh"),
-                root.join("Vintagestory.exe"), // future Windows port????????
-            ];
-
-            let bin = candidates.iter().find(|p| p.exists());
-            let Some(bin) = bin else {
-                self.instances.status_msg =
-                    Some(format!("Executable not found for {}", inst.name));
+    fn launch_instance(&mut self, req: LaunchRequest) {
+        let Some(inst) = self.instances.get(req.id) else { return };
+        if self.running.contains_key(&inst.id) {
+            self.notify.error(format!("{} is already running", inst.name));
+            return;
+        }
+        if let Some(warning) = req.world.as_deref().and_then(|w| worlds::launch_warning(inst, w)) {
+            self.confirm_world = Some((req, warning));
+            return;
+        }
+        self.check_data_in_use(req);
+    }
+
+    /// Holds the launch for confirmation when a game started elsewhere
+    /// already uses the instance's data folder.
+    fn check_data_in_use(&mut self, req: LaunchRequest) {
+        let Some(inst) = self.instances.get(req.id) else { return };
+        let ours: Vec<u32> = self.running.values().map(|p| p.pid()).collect();
+        if let Some(pid) = process::external_game_using(&inst.data_dir(), &ours) {
+            self.confirm_launch = Some(PendingLaunch { req, pid });
+            return;
+        }
+        self.prepare_launch(req);
+    }
+
+    /// Runs the instance's mod auto-update first when it wants one (and we
+    /// are online), then starts the game.
+    fn prepare_launch(&mut self, req: LaunchRequest) {
+        if self.config.block_missing_deps {
+            let problems = self.instances.dependency_problems(req.id);
+            if !problems.is_empty() {
+                let name = self.instances.get(req.id).map(|i| i.name.clone()).unwrap_or_default();
+                self.notify.error(format!("{name} was not launched: {}", problems.join("; ")));
                 return;
-            };
+            }
+        }
+        let Some(inst) = self.instances.get(req.id) else { return };
+        if self.mod_update.is_some() {
+            self.notify.error("Wait for the running mod update to finish");
+            return;
+        }
+        if !inst.auto_update_mods || self.net.is_offline() {
+            self.start_game(req);
+            return;
+        }
+        let cancel = Arc::new(AtomicBool::new(false));
+        let guard = self.activity.start(format!("Updating mods for {}", inst.name));
+        guard.set_cancel(cancel.clone());
+        let task = guard.task();
+        let (inst, flag) = (inst.clone(), cancel.clone());
+        std::thread::spawn(move || {
+            modupdate::run(&inst, &flag, &guard);
+        });
+        self.mod_update = Some(ModUpdate {
+            req,
+            task,
+            cancel,
+            current: "Reading mods…".into(),
+            progress: None,
+            log: Vec::new(),
+            updated: 0,
+        });
+    }
 
-            // ensure executable bit
-            if let Ok(meta) = std::fs::metadata(bin) {
-                let mut perms = meta.permissions();
-                if perms.mode() & 0o111 == 0 {
-                    perms.set_mode(perms.mode() | 0o755);
-                    let _ = std::fs::set_permissions(bin, perms);
+    /// Progress window for the pre-launch mod update; launches when it ends.
+    fn mod_update_dialog(&mut self, ctx: &egui::Context) {
+        let Some(job) = &mut self.mod_update else { return };
+        let mut finished = false;
+        loop {
+            match self.activity.try_take(job.task) {
+                Ok(UpdateEvent::Checking { index, total, name }) => {
+                    job.current = format!("Checking {}/{total}: {name}", index + 1);
+                    job.progress = None;
+                }
+                Ok(UpdateEvent::Progress(f)) => job.progress = Some(f),
+                Ok(UpdateEvent::Updated { name, from, to }) => {
+                    job.log.push(format!("✔ {name} {from} → {to}"));
+                    job.updated += 1;
                 }
+                Ok(UpdateEvent::Failed { name, error }) => job.log.push(format!("✖ {name}: {error}")),
+                Ok(UpdateEvent::Done) | Err(TryRecvError::Disconnected) => {
+                    finished = true;
+                    break;
+                }
+                Err(TryRecvError::Empty) => break,
             }
+        }
+        let name = self.instances.get(job.req.id).map(|i| i.name.clone()).unwrap_or_default();
+        let (mut skip, mut cancel) = (false, false);
+        egui::Window::new(format!("Updating mods for {name}"))
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(&job.current);
+                if let Some(p) = job.progress {
+                    ui.add(egui::ProgressBar::new(p).show_percentage());
+                }
+                for line in &job.log {
+                    ui.label(egui::RichText::new(line).small());
+                }
+                ui.horizontal(|ui| {
+                    skip = ui.button("Skip").on_hover_text("Launch now with the mods as they are").clicked();
+                    cancel = ui.button("Cancel").on_hover_text("Stop updating and don't launch").clicked();
+                });
+            });
+        if !finished && !skip && !cancel {
+            ctx.request_repaint_after(Duration::from_millis(100));
+            return;
+        }
+        job.cancel.store(true, Ordering::Relaxed);
+        let (updated, failed) = (job.updated, job.log.len() - job.updated);
+        let Some(ModUpdate { req, .. }) = self.mod_update.take() else { return };
+        if updated > 0 {
+            self.instances.mods_changed(req.id);
+        }
+        if cancel {
+            self.notify.success(format!("Launch of {name} cancelled"));
+            return;
+        }
+        if failed > 0 {
+            self.notify.error(format!("{name}: {updated} mod(s) updated, {failed} failed"));
+        } else if updated > 0 {
+            self.notify.success(format!("{name}: {updated} mod(s) updated"));
+        }
+        self.start_game(req);
+    }
 
-            // run via bash -c '<path>'
-            let result = std::process::Command::new("bash")
-                .arg("-c")
-                .arg(bin.to_string_lossy().to_string())
-                .current_dir(root)
-                .spawn();
+    fn start_game(&mut self, req: LaunchRequest) {
+        let Some(inst) = self.instances.get(req.id) else { return };
+        let opts = LaunchOptions { capture: true, world: req.world.clone() };
+        self.notify.result(match launch::launch(inst, opts) {
+            Ok(child) => {
+                let game = GameProcess::new(child);
+                let entry = RecentLog { id: inst.id, title: inst.name.clone(), started: modhistory::now(), log: game.log() };
+                if req.watch_log {
+                    self.log_view = Some(LogView::new(entry.clone()));
+                }
+                logview::remember(&mut self.recent_logs, entry);
+                self.running.insert(inst.id, game);
+                let world = req
+                    .world
+                    .as_deref()
+                    .and_then(|w| w.file_stem())
+                    .map(|w| format!(" into {}", w.to_string_lossy()))
+                    .unwrap_or_default();
+                let msg = format!("Launched {}{world}", inst.name);
+                self.instances.mark_played(req.id);
+                self.last_launch = Some(req);
+                Ok(msg)
+            }
+            Err(msg) => Err(msg),
+        });
+    }
+
+    fn log_window(&mut self, ctx: &egui::Context) {
+        let Some(view) = &mut self.log_view else { return };
+        let running = self.running.get(&view.id()).is_some_and(|g| view.shows(&g.log()));
+        if !view.ui(ctx, running, &self.recent_logs) {
+            self.log_view = None;
+        }
+    }
 
-            match result {
-                Ok(_) => {
-                    self.instances.status_msg =
-                        Some(format!("Launched {}", inst.name));
+    /// Every running background task with its progress, from any page.
+    fn tasks_window(&mut self, ctx: &egui::Context) {
+        if !self.show_tasks {
+            return;
+        }
+        let tasks = self.activity.tasks();
+        egui::Window::new("Tasks")
+            .open(&mut self.show_tasks)
+            .default_width(360.0)
+            .show(ctx, |ui| {
+                if tasks.is_empty() {
+                    ui.weak("Nothing running");
                 }
-                Err(e) => {
-                    self.instances.status_msg =
-                        Some(format!("Launch error: {e}"));
-                    eprintln!("launch failed: {e}");
+                for task in &tasks {
+                    ui.horizontal(|ui| {
+                        ui.label(&task.label);
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if task.cancel.is_some() && ui.small_button("Cancel").clicked() {
+                                activity::cancel(task);
+                            }
+                        });
+                    });
+                    match task.progress {
+                        Some(p) => ui.add(egui::ProgressBar::new(p).show_percentage()),
+                        None => ui.spinner(),
+                    };
                 }
+            });
+        if !tasks.is_empty() {
+            ctx.request_repaint_after(Duration::from_millis(200));
+        }
+    }
+
+    /// Re-probes the network now and then: every 30 s while offline, 5 min otherwise.
+    fn poll_connectivity(&mut self, ctx: &egui::Context) {
+        if let Some(task) = self.net_probe {
+            match self.activity.try_take(task) {
+                Ok(state) => {
+                    self.net = state;
+                    self.net_probe = None;
+                    ctx.request_repaint();
+                }
+                Err(TryRecvError::Disconnected) => self.net_probe = None,
+                Err(TryRecvError::Empty) => {}
             }
+            return;
+        }
+        let every = if self.net.is_online() { Duration::from_secs(300) } else { Duration::from_secs(30) };
+        if self.net_checked.is_some_and(|t| t.elapsed() < every) {
+            return;
+        }
+        self.net_checked = Some(Instant::now());
+        self.net_probe = Some(self.activity.spawn("Checking connectivity", |task| task.post(net::probe_connectivity())));
+        ctx.request_repaint_after(every);
+    }
+
+    /// Applies the "on exit" setting to the games still running.
+    fn close_requested(&mut self, ctx: &egui::Context) {
+        if self.running.is_empty() || self.exit_confirmed {
+            return;
+        }
+        match self.config.on_exit {
+            OnExit::Leave => {}
+            OnExit::Stop => self.stop_games(),
+            OnExit::Ask => {
+                ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+                self.confirm_exit.get_or_insert(false);
+            }
+        }
+    }
+
+    /// Asks every game started from the launcher to quit, killing the ones
+    /// that don't within a few seconds (one wait for all of them).
+    fn stop_games(&mut self) {
+        process::stop_all(self.running.drain().map(|(_, game)| game).collect(), Duration::from_secs(5));
+    }
+
+    fn confirm_exit_dialog(&mut self, ctx: &egui::Context) {
+        let Some(mut remember) = self.confirm_exit else { return };
+        let names: Vec<String> = self
+            .running
+            .keys()
+            .filter_map(|id| self.instances.get(*id).map(|i| i.name.clone()))
+            .collect();
+        let (mut choice, mut cancel) = (None, false);
+        egui::Window::new("Games still running")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(format!("Still running: {}", names.join(", ")));
+                ui.label("Stopping asks each game to quit (it saves first) and kills it after a few seconds.");
+                ui.horizontal(|ui| {
+                    if ui.button("Leave running").clicked() {
+                        choice = Some(OnExit::Leave);
+                    }
+                    if ui.button("Stop games").clicked() {
+                        choice = Some(OnExit::Stop);
+                    }
+                    cancel = ui.button("Cancel").clicked();
+                });
+                ui.checkbox(&mut remember, "Don't ask again")
+                    .on_hover_text("Can be changed under Settings → Instances");
+            });
+        if cancel {
+            self.confirm_exit = None;
+            return;
+        }
+        let Some(choice) = choice else {
+            self.confirm_exit = Some(remember);
+            return;
+        };
+        if remember {
+            self.config.on_exit = choice;
+            if let Err(e) = self.config.save() {
+                self.notify.error(format!("Could not save settings: {e}"));
+            }
+        }
+        if choice == OnExit::Stop {
+            self.stop_games();
+        }
+        self.confirm_exit = None;
+        self.exit_confirmed = true;
+        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+    }
+
+    /// Reaps exited games; a non-zero exit opens the crash dialog.
+    fn poll_games(&mut self, ctx: &egui::Context) {
+        let mut exited = Vec::new();
+        for (id, proc) in self.running.iter_mut() {
+            if let Some(status) = proc.try_exit() {
+                exited.push((*id, status, proc.stderr_tail(), proc.started()));
+            }
+        }
+        for (id, status, stderr, started) in exited {
+            self.running.remove(&id);
+            if let Some(inst) = self.instances.get(id) {
+                let _ = worlds::WorldVersions::stamp_saved_since(inst, started);
+            }
+            if status.success() {
+                continue;
+            }
+            let (instance, logs_dir) = self
+                .instances
+                .get(id)
+                .map(|i| (i.name.clone(), i.logs_dir()))
+                .unwrap_or_default();
+            self.crash = Some(CrashReport { instance, status: status.to_string(), stderr, logs_dir });
+        }
+        if !self.running.is_empty() {
+            ctx.request_repaint_after(Duration::from_secs(1));
+        }
+        self.crash_dialog(ctx);
+        self.confirm_launch_dialog(ctx);
+        self.confirm_world_dialog(ctx);
+        self.storage_dialog(ctx);
+        self.mod_update_dialog(ctx);
+        self.confirm_exit_dialog(ctx);
+    }
+
+    fn confirm_launch_dialog(&mut self, ctx: &egui::Context) {
+        let Some(pending) = &self.confirm_launch else { return };
+        let name = self.instances.get(pending.req.id).map(|i| i.name.clone()).unwrap_or_default();
+        let (mut go, mut cancel) = (false, false);
+        egui::Window::new("Game already running")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "Vintage Story (pid {}) is already running with {name}'s data folder.",
+                    pending.pid
+                ));
+                ui.label("A second copy may corrupt settings or worlds.");
+                ui.horizontal(|ui| {
+                    go = ui.button("Launch anyway").clicked();
+                    cancel = ui.button("Cancel").clicked();
+                });
+            });
+        if go {
+            if let Some(PendingLaunch { req, .. }) = self.confirm_launch.take() {
+                self.prepare_launch(req);
+            }
+        }
+        if cancel {
+            self.confirm_launch = None;
+        }
+    }
+
+    fn confirm_world_dialog(&mut self, ctx: &egui::Context) {
+        let Some((req, warning)) = &self.confirm_world else { return };
+        let world = req
+            .world
+            .as_deref()
+            .and_then(|w| w.file_stem())
+            .map(|w| w.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let (mut go, mut cancel) = (false, false);
+        egui::Window::new(format!("Open {world}?"))
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.colored_label(ui.visuals().warn_fg_color, format!("⚠ {world} may not be safe to open with this version."));
+                ui.label(warning);
+                ui.horizontal(|ui| {
+                    go = ui.button("Launch anyway").clicked();
+                    cancel = ui.button("Cancel").clicked();
+                });
+            });
+        if go {
+            if let Some((req, _)) = self.confirm_world.take() {
+                self.check_data_in_use(req);
+            }
+        }
+        if cancel {
+            self.confirm_world = None;
+        }
+    }
+
+    fn storage_dialog(&mut self, ctx: &egui::Context) {
+        if self.storage_problems.is_empty() {
+            return;
+        }
+        let (mut retry, mut dismiss) = (false, false);
+        egui::Window::new("Cannot write launcher data")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label("Downloads and new instances will fail until this is fixed:");
+                for problem in &self.storage_problems {
+                    ui.colored_label(ui.visuals().error_fg_color, problem);
+                }
+                ui.separator();
+                ui.label("Give your user write access to the folder, or start the launcher with");
+                ui.label("$XDG_DATA_HOME pointing somewhere writable. Instance data can also live");
+                ui.label("elsewhere: Settings → Instances → New instance data in.");
+                ui.horizontal(|ui| {
+                    retry = ui.button("Check again").clicked();
+                    dismiss = ui.button("Dismiss").clicked();
+                });
+            });
+        if retry {
+            self.storage_problems = preflight::storage_problems(&self.config);
+        }
+        if dismiss {
+            self.storage_problems.clear();
+        }
+    }
+
+    fn crash_dialog(&mut self, ctx: &egui::Context) {
+        let Some(report) = &self.crash else { return };
+        let mut open = true;
+        egui::Window::new(format!("{} exited unexpectedly", report.instance))
+            .collapsible(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(format!("Exit status: {}", report.status));
+                ui.separator();
+                if report.stderr.is_empty() {
+                    ui.label(egui::RichText::new("The game wrote nothing to stderr.").weak());
+                } else {
+                    egui::ScrollArea::vertical()
+                        .max_height(300.0)
+                        .stick_to_bottom(true)
+                        .show(ui, |ui| {
+                            ui.add(egui::Label::new(
+                                egui::RichText::new(report.stderr.join("\n")).monospace(),
+                            ));
+                        });
+                }
+                ui.separator();
+                let log = report.logs_dir.join("client-main.log");
+                ui.label(format!("Full game log: {}", log.display()));
+                if ui.button("📜 Open game logs").clicked() {
+                    let _ = open::that(&report.logs_dir);
+                }
+            });
+        if !open {
+            self.crash = None;
         }
     }
 }
 
 
 fn main() -> eframe::Result<()> {
+    if paths::data_home().is_none() {
+        eprintln!("vs_launcher: cannot find a home directory for launcher data; set $HOME (or $XDG_DATA_HOME)");
+        std::process::exit(1);
+    }
+    paths::use_base_dir(Config::load().data_dir());
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = cli::run(&args) {
+        std::process::exit(code);
+    }
     eframe::run_native("Vintage Story Launcher", eframe::NativeOptions::default(), Box::new(|_| Box::<VsLauncherApp>::default()))
 }
@@ -0,0 +1,249 @@
+//! modhistory.rs – per-instance `instance_history.json`: which mods were
+//! installed, updated or removed, and when
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    disk,
+    modinfo::{self, ModInfo},
+    pages::instances::Instance,
+    schema::{self, Versioned},
+};
+
+/// Older entries are dropped once the log grows past this.
+const MAX_ENTRIES: usize = 500;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Change {
+    Installed,
+    Updated,
+    Removed,
+    /// a kept copy put back from the history
+    Restored,
+}
+
+impl Change {
+    pub fn label(self) -> &'static str {
+        match self {
+            Change::Installed => "installed",
+            Change::Updated => "updated",
+            Change::Removed => "removed",
+            Change::Restored => "restored",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Entry {
+    /// unix seconds
+    pub at:      u64,
+    pub change:  Change,
+    pub modid:   String,
+    pub name:    String,
+    /// version after the change; the one that went away for `Removed`
+    pub version: String,
+    /// version before an update or restore
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from:    Option<String>,
+    /// copy of the file the change replaced or removed, for rolling back
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kept:    Option<PathBuf>,
+}
+
+impl Entry {
+    /// `change` of the mod described by `info`, stamped now.
+    pub fn new(change: Change, info: &ModInfo) -> Self {
+        Self {
+            at: now(),
+            change,
+            modid: info.modid.clone(),
+            name: info.name.clone(),
+            version: info.version.clone(),
+            from: None,
+            kept: None,
+        }
+    }
+
+    /// Version of the kept copy: what was there before the change.
+    pub fn kept_version(&self) -> &str {
+        match self.change {
+            Change::Removed => &self.version,
+            _ => self.from.as_deref().unwrap_or(&self.version),
+        }
+    }
+
+    /// The kept copy, if there is one and it is still on disk.
+    pub fn restorable(&self) -> Option<&Path> {
+        self.kept.as_deref().filter(|p| p.exists())
+    }
+}
+
+/// Oldest first, as written.
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct ModHistory {
+    #[serde(default)]
+    pub entries: Vec<Entry>,
+}
+
+impl Versioned for ModHistory {
+    const SCHEMA: u64 = 1;
+}
+
+impl ModHistory {
+    fn path(inst: &Instance) -> PathBuf {
+        inst.data_dir().join("instance_history.json")
+    }
+
+    /// where `keep` copies files to
+    fn kept_dir(inst: &Instance) -> PathBuf {
+        inst.data_dir().join("mod-history")
+    }
+
+    /// The instance's history; empty if there is none.
+    pub fn load(inst: &Instance) -> Self {
+        schema::load(&Self::path(inst))
+    }
+
+    pub fn save(&self, inst: &Instance) -> io::Result<()> {
+        schema::save(&Self::path(inst), self)
+    }
+
+    fn push(&mut self, entry: Entry) {
+        self.entries.push(entry);
+        let over = self.entries.len().saturating_sub(MAX_ENTRIES);
+        self.entries.drain(..over);
+    }
+
+    /// Appends `entry` to the instance's history file.
+    pub fn record(inst: &Instance, entry: Entry) -> io::Result<()> {
+        let mut history = Self::load(inst);
+        history.push(entry);
+        history.save(inst)
+    }
+
+    /// Copies a mod file (or folder) aside before it is replaced or removed;
+    /// returns where the copy went.
+    pub fn keep(inst: &Instance, path: &Path) -> io::Result<PathBuf> {
+        let name = path.file_name().ok_or_else(|| io::Error::other("not a file"))?;
+        let dest = Self::kept_dir(inst).join(now().to_string()).join(name);
+        disk::copy_recursive(path, &dest)?;
+        Ok(dest)
+    }
+
+    /// Puts the kept copy from `entry` back into the mods folder, replacing
+    /// whatever version of that mod is there now (which is kept in turn).
+    pub fn restore(inst: &Instance, entry: &Entry) -> Result<(), String> {
+        let kept = entry.restorable().ok_or("The kept copy is gone")?;
+        let name = kept.file_name().ok_or("The kept copy has no file name")?;
+        let mods_dir = inst.mods_dir();
+        let mut restored = Entry {
+            at: now(),
+            change: Change::Restored,
+            modid: entry.modid.clone(),
+            name: entry.name.clone(),
+            version: entry.kept_version().to_string(),
+            from: None,
+            kept: None,
+        };
+        let current = modinfo::list_mods(&mods_dir).into_iter().filter_map(|m| {
+            let info = m.info.ok().filter(|i| i.modid.eq_ignore_ascii_case(&entry.modid))?;
+            Some((m.path, info.version))
+        });
+        for (path, version) in current {
+            restored.kept = Some(Self::keep(inst, &path).map_err(|e| format!("Cannot keep {}: {e}", path.display()))?);
+            restored.from = Some(version);
+            let res = if path.is_dir() { fs::remove_dir_all(&path) } else { fs::remove_file(&path) };
+            res.map_err(|e| format!("Cannot remove {}: {e}", path.display()))?;
+        }
+        disk::copy_recursive(kept, &mods_dir.join(name)).map_err(|e| format!("Restore error: {e}"))?;
+        Self::record(inst, restored).map_err(|e| format!("Could not save instance_history.json: {e}"))
+    }
+}
+
+pub fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// How long before `now` the unix time `secs` was: "just now",
+/// "5 min ago", "2 hours ago", "3 days ago"; older than a month falls back
+/// to `format_time`.
+pub fn format_ago(secs: u64, now: u64) -> String {
+    let plural = |n: u64, unit: &str| format!("{n} {unit}{} ago", if n == 1 { "" } else { "s" });
+    match now.saturating_sub(secs) {
+        0..=59 => "just now".into(),
+        d @ 60..=3_599 => format!("{} min ago", d / 60),
+        d @ 3_600..=86_399 => plural(d / 3_600, "hour"),
+        d @ 86_400..=2_591_999 => plural(d / 86_400, "day"),
+        _ => format_time(secs),
+    }
+}
+
+/// "2026-10-15 14:03 UTC"
+pub fn format_time(secs: u64) -> String {
+    // days → civil date, after Howard Hinnant's `civil_from_days`
+    let days = (secs / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let doe = days.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+    let (hour, minute) = (secs % 86_400 / 3_600, secs % 3_600 / 60);
+    format!("{year:04}-{month:02}-{day:02} {hour:02}:{minute:02} UTC")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::instance;
+
+    fn info(version: &str) -> ModInfo {
+        ModInfo { modid: "carryon".into(), name: "Carry On".into(), version: version.into(), dependencies: Vec::new() }
+    }
+
+    #[test]
+    fn dates_format_as_utc() {
+        assert_eq!(format_time(0), "1970-01-01 00:00 UTC");
+        assert_eq!(format_time(951_782_400 + 3_723), "2000-02-29 01:02 UTC");
+        assert_eq!(format_time(1_792_065_780), "2026-10-15 12:03 UTC");
+    }
+
+    #[test]
+    fn entries_append_and_reload() {
+        let tmp = tempfile::tempdir().unwrap();
+        let inst = instance(tmp.path());
+        ModHistory::record(&inst, Entry::new(Change::Installed, &info("1.0.0"))).unwrap();
+        let update = Entry { version: "1.1.0".into(), from: Some("1.0.0".into()), ..Entry::new(Change::Updated, &info("1.0.0")) };
+        ModHistory::record(&inst, update).unwrap();
+        let changes: Vec<_> = ModHistory::load(&inst).entries.iter().map(|e| (e.change, e.kept_version().to_string())).collect();
+        assert_eq!(changes, [(Change::Installed, "1.0.0".into()), (Change::Updated, "1.0.0".into())]);
+    }
+
+    #[test]
+    fn log_is_capped_oldest_first() {
+        let mut history = ModHistory::default();
+        for i in 0..MAX_ENTRIES + 2 {
+            history.push(Entry::new(Change::Installed, &info(&i.to_string())));
+        }
+        assert_eq!(history.entries.len(), MAX_ENTRIES);
+        assert_eq!(history.entries[0].version, "2");
+    }
+
+    #[test]
+    fn ages_read_naturally() {
+        let now = 1_760_000_000;
+        assert_eq!(format_ago(now - 20, now), "just now");
+        assert_eq!(format_ago(now - 5 * 60, now), "5 min ago");
+        assert_eq!(format_ago(now - 3_600, now), "1 hour ago");
+        assert_eq!(format_ago(now - 2 * 86_400, now), "2 days ago");
+        assert_eq!(format_ago(0, now), format_time(0));
+    }
+}
@@ -0,0 +1,296 @@
+//! modinfo.rs – read `modinfo.json` from mods installed in an instance
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use regex::Regex;
+use serde_json::Value;
+
+use crate::pages::versions::VersionPage;
+
+/// Dependency ids that ship with the game itself.
+const BUILTIN_MODS: &[&str] = &["game", "survival", "creative"];
+
+/// The parts of `modinfo.json` the launcher cares about.
+#[derive(Clone, Debug)]
+pub struct ModInfo {
+    pub modid:   String,
+    pub name:    String,
+    pub version: String,
+    /// `(modid, minimum version)`; an empty or `*` version means any
+    pub dependencies: Vec<(String, String)>,
+}
+
+#[derive(Debug)]
+pub enum ModInfoError {
+    /// not something the game would load (e.g. a readme next to the zips)
+    NotAMod,
+    /// looks like a mod, but its modinfo is missing or malformed
+    Unreadable(String),
+}
+
+/// One entry of an instance's mods folder.
+pub struct InstalledMod {
+    pub path: PathBuf,
+    pub info: Result<ModInfo, String>,
+}
+
+/// The three shapes the game loads from a mods folder.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ModKind {
+    Zip,
+    Folder,
+    /// a loose `.cs` file compiled by the game at startup
+    Source,
+}
+
+impl ModKind {
+    pub fn of(path: &Path) -> Option<Self> {
+        if path.is_dir() {
+            return Some(ModKind::Folder);
+        }
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "zip" => Some(ModKind::Zip),
+            "cs" => Some(ModKind::Source),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ModKind::Zip => "zip",
+            ModKind::Folder => "folder",
+            ModKind::Source => "source",
+        }
+    }
+}
+
+impl InstalledMod {
+    pub fn kind(&self) -> Option<ModKind> {
+        ModKind::of(&self.path)
+    }
+
+    pub fn file_name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+}
+
+/// Lists everything in `dir` that the game would treat as a mod, sorted by file name.
+/// Mods whose modinfo can't be read are kept with the error.
+pub fn list_mods(dir: &Path) -> Vec<InstalledMod> {
+    let Ok(rd) = fs::read_dir(dir) else { return Vec::new() };
+    let mut out: Vec<InstalledMod> = rd
+        .flatten()
+        .filter_map(|e| {
+            let path = e.path();
+            match read_modinfo(&path) {
+                Ok(info) => Some(InstalledMod { path, info: Ok(info) }),
+                Err(ModInfoError::Unreadable(e)) => Some(InstalledMod { path, info: Err(e) }),
+                Err(ModInfoError::NotAMod) => None,
+            }
+        })
+        .collect();
+    out.sort_by_key(|m| m.file_name().to_lowercase());
+    out
+}
+
+/// Reads the modinfo of a zip, folder or `.cs` source mod.
+pub fn read_modinfo(path: &Path) -> Result<ModInfo, ModInfoError> {
+    match ModKind::of(path).ok_or(ModInfoError::NotAMod)? {
+        ModKind::Zip => {
+            let text = read_zip_entry(path, "modinfo.json").map_err(ModInfoError::Unreadable)?;
+            parse_modinfo(&text).map_err(ModInfoError::Unreadable)
+        }
+        ModKind::Folder => {
+            let text = read_folder_entry(path, "modinfo.json").map_err(ModInfoError::Unreadable)?;
+            parse_modinfo(&text).map_err(ModInfoError::Unreadable)
+        }
+        ModKind::Source => {
+            let text = fs::read_to_string(path).map_err(|e| ModInfoError::Unreadable(e.to_string()))?;
+            Ok(parse_source_modinfo(path, &text))
+        }
+    }
+}
+
+fn read_folder_entry(dir: &Path, wanted: &str) -> Result<String, String> {
+    let entry = fs::read_dir(dir)
+        .map_err(|e| e.to_string())?
+        .flatten()
+        .find(|e| e.file_name().to_string_lossy().eq_ignore_ascii_case(wanted))
+        .ok_or_else(|| format!("no {wanted} in folder"))?;
+    fs::read_to_string(entry.path()).map_err(|e| e.to_string())
+}
+
+fn read_zip_entry(path: &Path, wanted: &str) -> Result<String, String> {
+    let mut file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let entries = compress_tools::list_archive_files(&mut file).map_err(|e| e.to_string())?;
+    // the game matches this name case-insensitively
+    let entry = entries
+        .iter()
+        .find(|n| n.eq_ignore_ascii_case(wanted))
+        .ok_or_else(|| format!("no {wanted} in archive"))?;
+
+    let mut file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut buf = Vec::new();
+    compress_tools::uncompress_archive_file(&mut file, &mut buf, entry).map_err(|e| e.to_string())?;
+    Ok(String::from_utf8_lossy(&buf).into_owned())
+}
+
+/// modinfo keys are case-insensitive ("ModID", "modid", …), so lower-case them first.
+pub fn parse_modinfo(text: &str) -> Result<ModInfo, String> {
+    let json: Value = serde_json::from_str(text.trim_start_matches('\u{feff}'))
+        .map_err(|e| format!("invalid modinfo.json: {e}"))?;
+    let obj = json.as_object().ok_or("modinfo.json is not an object")?;
+    let get = |key: &str| {
+        obj.iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v)
+    };
+    let text_of = |key: &str| get(key).and_then(Value::as_str).unwrap_or("").to_string();
+
+    let name = text_of("name");
+    let modid = text_of("modid");
+    if name.is_empty() && modid.is_empty() {
+        return Err("modinfo.json has neither name nor modid".into());
+    }
+
+    Ok(ModInfo {
+        // modids are compared lower-case; without one the game derives it from the name
+        modid: if modid.is_empty() {
+            name.to_lowercase().chars().filter(|c| c.is_ascii_alphanumeric()).collect()
+        } else {
+            modid.to_lowercase()
+        },
+        name,
+        version: text_of("version"),
+        dependencies: get("dependencies")
+            .and_then(Value::as_object)
+            .map(|deps| {
+                deps.iter()
+                    .map(|(id, v)| (id.to_lowercase(), v.as_str().unwrap_or("").to_string()))
+                    .collect()
+            })
+            .unwrap_or_default(),
+    })
+}
+
+/// Unmet dependencies across an instance's mods, one message each.
+/// `game` is the instance's game version, checked against `"game"` deps.
+pub fn check_dependencies(mods: &[InstalledMod], game: &str) -> Vec<String> {
+    let infos: Vec<&ModInfo> = mods.iter().filter_map(|m| m.info.as_ref().ok()).collect();
+    let too_old = |have: &str, need: &str| {
+        !need.is_empty() && need != "*" && VersionPage::version_cmp(have, need).is_lt()
+    };
+    let mut problems = Vec::new();
+    for info in &infos {
+        for (dep, need) in &info.dependencies {
+            if dep == "game" {
+                if too_old(game, need) {
+                    problems.push(format!("{} needs game {need} or newer (instance has {game})", info.name));
+                }
+                continue;
+            }
+            if BUILTIN_MODS.contains(&dep.as_str()) {
+                continue;
+            }
+            match infos.iter().find(|i| i.modid == *dep) {
+                None => problems.push(format!("{} needs {dep}, which is not installed", info.name)),
+                Some(found) if too_old(&found.version, need) => problems.push(format!(
+                    "{} needs {dep} {need} or newer, found {}",
+                    info.name, found.version
+                )),
+                Some(_) => {}
+            }
+        }
+    }
+    problems
+}
+
+/// Source mods declare themselves with `[assembly: ModInfo("Name", "modid", Version = "1.0.0")]`.
+/// Without the attribute the file name stands in for name and modid.
+fn parse_source_modinfo(path: &Path, text: &str) -> ModInfo {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let attr = Regex::new(r#"ModInfo\(\s*"([^"]*)"(?:\s*,\s*"([^"]*)")?([^\]]*)\)"#).unwrap();
+    let version = Regex::new(r#"Version\s*=\s*"([^"]*)""#).unwrap();
+
+    let Some(caps) = attr.captures(text) else {
+        return ModInfo {
+            modid: stem.to_lowercase(),
+            name: stem,
+            version: String::new(),
+            dependencies: Vec::new(),
+        };
+    };
+    let name = caps[1].to_string();
+    let modid = caps
+        .get(2)
+        .map(|m| m.as_str().to_lowercase())
+        .unwrap_or_else(|| name.to_lowercase().chars().filter(|c| c.is_ascii_alphanumeric()).collect());
+    let version = caps
+        .get(3)
+        .and_then(|rest| version.captures(rest.as_str()))
+        .map(|c| c[1].to_string())
+        .unwrap_or_default();
+    ModInfo { modid, name, version, dependencies: Vec::new() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unreadable_mods_are_listed_not_skipped() {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::create_dir(tmp.path().join("carryon")).unwrap();
+        fs::write(tmp.path().join("carryon/modinfo.json"), r#"{"ModID": "CarryOn", "Name": "Carry On", "Version": "1.8.0"}"#).unwrap();
+        fs::write(tmp.path().join("broken.zip"), b"not a zip").unwrap();
+        fs::write(tmp.path().join("readme.txt"), b"hi").unwrap();
+
+        let mods = list_mods(tmp.path());
+        let names: Vec<String> = mods.iter().map(InstalledMod::file_name).collect();
+        assert_eq!(names, ["broken.zip", "carryon"]);
+        assert!(mods[0].info.is_err());
+        let info = mods[1].info.as_ref().unwrap();
+        assert_eq!((info.modid.as_str(), info.name.as_str(), info.version.as_str()), ("carryon", "Carry On", "1.8.0"));
+    }
+
+    /// Writes a folder mod with this modinfo into `dir`.
+    fn folder_mod(dir: &Path, modinfo: &str) {
+        let info = parse_modinfo(modinfo).unwrap();
+        let path = dir.join(&info.modid);
+        fs::create_dir(&path).unwrap();
+        fs::write(path.join("modinfo.json"), modinfo).unwrap();
+    }
+
+    #[test]
+    fn unmet_dependencies_are_reported() {
+        let tmp = tempfile::tempdir().unwrap();
+        folder_mod(tmp.path(), r#"{"modid": "xlib", "name": "XLib", "version": "0.7.0"}"#);
+        folder_mod(
+            tmp.path(),
+            r#"{"modid": "skills", "name": "XSkills", "version": "1.0.0",
+                "dependencies": {"game": "1.20.0", "xlib": "0.8.0", "survival": ""}}"#,
+        );
+        folder_mod(tmp.path(), r#"{"modid": "pets", "name": "Pets", "dependencies": {"petcore": "*"}}"#);
+
+        let mods = list_mods(tmp.path());
+        let problems = check_dependencies(&mods, "1.19.8");
+        assert_eq!(
+            problems,
+            [
+                "Pets needs petcore, which is not installed",
+                "XSkills needs game 1.20.0 or newer (instance has 1.19.8)",
+                "XSkills needs xlib 0.8.0 or newer, found 0.7.0",
+            ]
+        );
+        assert_eq!(check_dependencies(&mods[1..], "1.20.0"), ["XSkills needs xlib 0.8.0 or newer, found 0.7.0"]);
+    }
+}
@@ -0,0 +1,95 @@
+//! modlock.rs – per-instance `modlock.json` pinning exact mod versions
+use std::{collections::BTreeMap, fs, io, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    modinfo::InstalledMod,
+    pages::instances::Instance,
+    schema::{self, Versioned},
+};
+
+/// modid → pinned version. Installs and updates keep a pinned mod at its
+/// version until it is unlocked, so every client can run the same set.
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct ModLock {
+    #[serde(default)]
+    pub mods: BTreeMap<String, String>,
+}
+
+impl Versioned for ModLock {
+    const SCHEMA: u64 = 1;
+
+    /// Unversioned files could hold hand-written mixed-case ids, which
+    /// `pinned` never matches; ids are lowercase from v1 on.
+    fn migrate(from: u64, mut raw: Value) -> Value {
+        if from < 1 {
+            if let Some(Value::Object(mods)) = raw.get_mut("mods") {
+                *mods = std::mem::take(mods).into_iter().map(|(id, v)| (id.to_lowercase(), v)).collect();
+            }
+        }
+        raw
+    }
+}
+
+impl ModLock {
+    fn path(inst: &Instance) -> PathBuf {
+        inst.data_dir().join("modlock.json")
+    }
+
+    /// The instance's lock file; empty if there is none.
+    pub fn load(inst: &Instance) -> Self {
+        schema::load(&Self::path(inst))
+    }
+
+    /// Writes the lock file, or removes it once nothing is pinned.
+    pub fn save(&self, inst: &Instance) -> io::Result<()> {
+        let path = Self::path(inst);
+        if self.mods.is_empty() {
+            return match fs::remove_file(path) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            };
+        }
+        schema::save(&path, self)
+    }
+
+    pub fn pinned(&self, modid: &str) -> Option<&str> {
+        self.mods.get(&modid.to_lowercase()).map(String::as_str)
+    }
+
+    pub fn lock(&mut self, modid: &str, version: &str) {
+        self.mods.insert(modid.to_lowercase(), version.to_string());
+    }
+
+    pub fn unlock(&mut self, modid: &str) {
+        self.mods.remove(&modid.to_lowercase());
+    }
+
+    /// Pins every readable mod at its installed version.
+    pub fn lock_all(&mut self, mods: &[InstalledMod]) {
+        for info in mods.iter().filter_map(|m| m.info.as_ref().ok()) {
+            self.lock(&info.modid, &info.version);
+        }
+    }
+
+    /// Pinned mods that are missing or installed at another version.
+    pub fn drift(&self, mods: &[InstalledMod]) -> Vec<String> {
+        self.mods
+            .iter()
+            .filter_map(|(id, want)| {
+                let have = mods
+                    .iter()
+                    .filter_map(|m| m.info.as_ref().ok())
+                    .find(|i| i.modid == *id)
+                    .map(|i| i.version.as_str());
+                match have {
+                    None => Some(format!("{id} {want} is pinned but not installed")),
+                    Some(v) if v != want => Some(format!("{id} is pinned to {want}, found {v}")),
+                    Some(_) => None,
+                }
+            })
+            .collect()
+    }
+}
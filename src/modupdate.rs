@@ -0,0 +1,110 @@
+//! modupdate.rs – bring an instance's mods up to their newest compatible release
+use std::{
+    cmp::Ordering,
+    fs,
+    sync::atomic::{AtomicBool, Ordering as AtomicOrdering},
+};
+
+use crate::{
+    activity::ActivityGuard,
+    modhistory::{Change, Entry, ModHistory},
+    modinfo::{self, ModKind},
+    modlock::ModLock,
+    net,
+    pages::{instances::Instance, mods, versions::VersionPage},
+};
+
+pub enum UpdateEvent {
+    /// looking at mod `index` of `total`
+    Checking { index: usize, total: usize, name: String },
+    /// download progress of the current mod, 0.0‒1.0
+    Progress(f32),
+    Updated { name: String, from: String, to: String },
+    Failed { name: String, error: String },
+    Done,
+}
+
+/// Updates every zip mod of `inst` that isn't pinned in its modlock.json
+/// to the newest release tagged for the instance's game version. Blocking;
+/// stops between mods (and mid-download) once `cancel` is raised. `task`
+/// mirrors the progress for the Tasks window and posts the `UpdateEvent`s.
+pub fn run(inst: &Instance, cancel: &AtomicBool, task: &ActivityGuard) {
+    let dir = inst.mods_dir();
+    let lock = ModLock::load(inst);
+    // folder and .cs mods have no single file to swap
+    let mods: Vec<_> = modinfo::list_mods(&dir)
+        .into_iter()
+        .filter(|m| m.kind() == Some(ModKind::Zip))
+        .filter_map(|m| m.info.ok().map(|info| (m.path, info)))
+        .filter(|(_, info)| lock.pinned(&info.modid).is_none())
+        .collect();
+    let total = mods.len();
+
+    for (index, (path, info)) in mods.into_iter().enumerate() {
+        if cancel.load(AtomicOrdering::Relaxed) {
+            return;
+        }
+        task.set_label(format!("Updating mods for {}: {}", inst.name, info.name));
+        task.set_progress(index as f32 / total as f32);
+        task.post(UpdateEvent::Checking { index, total, name: info.name.clone() });
+        let release = match mods::fetch_releases(&info.modid) {
+            Ok(releases) => mods::newest_compatible(&releases, &inst.version).cloned(),
+            Err(error) => {
+                task.post(UpdateEvent::Failed { name: info.name, error });
+                continue;
+            }
+        };
+        let Some(release) = release else { continue };
+        if VersionPage::version_cmp(&release.modversion, &info.version) != Ordering::Greater
+            || release.mainfile.is_empty()
+        {
+            continue;
+        }
+
+        // a bare name from the API or the fallback; never a path out of `dir`
+        let file = mods::release_file(&release, &info.modid);
+        let part = dir.join(format!("{file}.part"));
+        let got = net::download_to_file(
+            &release.mainfile,
+            &part,
+            cancel,
+            |done, len| {
+                if let Some(len) = len.filter(|&l| l > 0) {
+                    task.post(UpdateEvent::Progress(done as f32 / len as f32));
+                }
+            },
+            &mut |n| task.set_label(format!("Updating mods for {}: {} (retry {n} of {})", inst.name, info.name, net::RETRIES)),
+        );
+        let swapped = got.map_err(|e| e.to_string()).and_then(|_| {
+            if cancel.load(AtomicOrdering::Relaxed) {
+                return Err("cancelled".into());
+            }
+            // a copy of the old zip lets the history roll the update back
+            let kept = ModHistory::keep(inst, &path).ok();
+            let dest = dir.join(&file);
+            fs::rename(&part, &dest).map_err(|e| e.to_string())?;
+            // the new zip usually has another name; drop the old one
+            if dest != path {
+                fs::remove_file(&path).map_err(|e| e.to_string())?;
+            }
+            Ok(kept)
+        });
+        match swapped {
+            Ok(kept) => {
+                let entry = Entry {
+                    version: release.modversion.clone(),
+                    from: Some(info.version.clone()),
+                    kept,
+                    ..Entry::new(Change::Updated, &info)
+                };
+                let _ = ModHistory::record(inst, entry);
+                task.post(UpdateEvent::Updated { name: info.name, from: info.version, to: release.modversion });
+            }
+            Err(error) => {
+                let _ = fs::remove_file(&part);
+                task.post(UpdateEvent::Failed { name: info.name, error });
+            }
+        }
+    }
+    task.post(UpdateEvent::Done);
+}
@@ -0,0 +1,565 @@
+//! net.rs – HTTP helpers shared by every download
+use std::{
+    collections::VecDeque,
+    fmt, fs,
+    io::{self, Read, Write},
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, OnceLock,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use reqwest::{
+    blocking::{Client, RequestBuilder, Response},
+    header::{HeaderMap, RANGE, RETRY_AFTER},
+    StatusCode,
+};
+use serde_json::Value;
+
+use crate::config::Config;
+
+/// Result of the last connectivity probe.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Connectivity {
+    /// no probe has finished yet – pages hold off on auto-fetching
+    #[default]
+    Unknown,
+    Online,
+    Offline,
+}
+
+impl Connectivity {
+    pub fn is_online(self) -> bool {
+        self == Connectivity::Online
+    }
+    pub fn is_offline(self) -> bool {
+        self == Connectivity::Offline
+    }
+}
+
+/// Longest wait to connect to a server.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Longest wait for a response, and then for each read of its body, so a
+/// stalled connection fails instead of hanging its worker.
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The HTTP client every request goes through, with the timeouts above.
+/// Built on first use; clones of it share one connection pool.
+pub fn client() -> &'static Client {
+    static CLIENT: OnceLock<Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .timeout(TIMEOUT)
+            .build()
+            .unwrap_or_else(|e| {
+                eprintln!("HTTP client setup failed, using defaults: {e}");
+                Client::new()
+            })
+    })
+}
+
+/// Fast HEAD against the mod API; blocking, so run it on a worker.
+pub fn probe_connectivity() -> Connectivity {
+    let reachable = client()
+        .head("https://mods.vintagestory.at/api/gameversions")
+        .timeout(Duration::from_secs(3))
+        .send()
+        .is_ok();
+    if reachable {
+        Connectivity::Online
+    } else {
+        Connectivity::Offline
+    }
+}
+
+/*────────── JSON API calls ─────────*/
+#[derive(Debug)]
+pub enum ApiError {
+    /// HTTP 429; the server's Retry-After, when it sent one in seconds
+    RateLimited(Option<Duration>),
+    Failed(String),
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::RateLimited(Some(d)) => write!(f, "rate limited, try again in {}s", d.as_secs()),
+            ApiError::RateLimited(None) => write!(f, "rate limited, try again shortly"),
+            ApiError::Failed(msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl From<String> for ApiError {
+    fn from(msg: String) -> Self {
+        ApiError::Failed(msg)
+    }
+}
+
+/// Extra tries after a request that failed to connect or timed out.
+pub const RETRIES: u32 = 3;
+
+/// Pause before retry `n` (from 1): 1 s, 2 s, 4 s.
+fn backoff(n: u32) -> Duration {
+    Duration::from_secs(1 << n.saturating_sub(1).min(5))
+}
+
+/// Runs `op`, and again up to `RETRIES` times with backoff while it fails
+/// with an error `transient` accepts. `on_retry(n)` runs before retry `n`;
+/// `sleep` does the waiting.
+fn retry_with<T, E>(
+    mut op: impl FnMut() -> Result<T, E>,
+    transient: impl Fn(&E) -> bool,
+    on_retry: &mut dyn FnMut(u32),
+    sleep: impl Fn(Duration),
+) -> Result<T, E> {
+    let mut n = 0;
+    loop {
+        match op() {
+            Err(e) if n < RETRIES && transient(&e) => {
+                n += 1;
+                on_retry(n);
+                sleep(backoff(n));
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Sends `req`, retrying on connection failures and timeouts (an HTTP
+/// status, 404 included, is an answer and comes back as is). `on_retry(n)`
+/// lets the caller show that retry `n` of `RETRIES` is coming.
+pub fn send(req: RequestBuilder, on_retry: &mut dyn FnMut(u32)) -> reqwest::Result<Response> {
+    if req.try_clone().is_none() {
+        // a streamed body can't be sent twice
+        return req.send();
+    }
+    retry_with(
+        || req.try_clone().expect("cloned above").send(),
+        |e: &reqwest::Error| e.is_connect() || e.is_timeout(),
+        on_retry,
+        thread::sleep,
+    )
+}
+
+/// Sends `req` and parses the JSON body. A 429 comes back as
+/// `RateLimited` so the caller can back off instead of failing.
+pub fn get_json(req: RequestBuilder) -> Result<Value, ApiError> {
+    get_json_retrying(req, &mut |_| {})
+}
+
+/// `get_json`, telling `on_retry` about each retry as `send` does.
+pub fn get_json_retrying(req: RequestBuilder, on_retry: &mut dyn FnMut(u32)) -> Result<Value, ApiError> {
+    let resp = send(req, on_retry).map_err(|e| ApiError::Failed(e.to_string()))?;
+    let status = resp.status();
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        return Err(ApiError::RateLimited(retry_after(resp.headers())));
+    }
+    if !status.is_success() {
+        return Err(ApiError::Failed(format!("server answered {status}")));
+    }
+    resp.json().map_err(|e| ApiError::Failed(e.to_string()))
+}
+
+/// Retry-After in its delay-seconds form (the HTTP-date form is ignored).
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let secs = headers.get(RETRY_AFTER)?.to_str().ok()?.trim().parse().ok()?;
+    Some(Duration::from_secs(secs))
+}
+
+#[derive(Debug)]
+pub enum DownloadError {
+    Http(reqwest::Error),
+    Status(StatusCode),
+    Io(io::Error),
+    /// the connection broke while the body was arriving
+    Dropped(io::Error),
+    /// the body ended before `Content-Length` bytes arrived
+    SizeMismatch { expected: u64, got: u64 },
+    Cancelled,
+}
+
+impl fmt::Display for DownloadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DownloadError::Http(e) => write!(f, "network error: {e}"),
+            DownloadError::Status(s) => write!(f, "server answered {s}"),
+            DownloadError::Io(e) => write!(f, "disk error: {e}"),
+            DownloadError::Dropped(e) => write!(f, "connection lost: {e}"),
+            DownloadError::SizeMismatch { expected, got } => {
+                write!(f, "download truncated ({got} of {expected} bytes)")
+            }
+            DownloadError::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for DownloadError {}
+
+impl DownloadError {
+    /// Worth another try: the connection failed or broke off, or the body
+    /// came up short. What arrived stays in the file and is continued.
+    fn transient(&self) -> bool {
+        match self {
+            DownloadError::Http(e) => e.is_connect() || e.is_timeout(),
+            DownloadError::Dropped(_) => true,
+            DownloadError::SizeMismatch { expected, got } => got < expected,
+            _ => false,
+        }
+    }
+}
+
+impl From<io::Error> for DownloadError {
+    fn from(e: io::Error) -> Self {
+        DownloadError::Io(e)
+    }
+}
+
+impl From<reqwest::Error> for DownloadError {
+    fn from(e: reqwest::Error) -> Self {
+        DownloadError::Http(e)
+    }
+}
+
+/// What a mirror speed test measured.
+pub struct Speed {
+    /// request sent → response headers back
+    pub latency:    Duration,
+    /// body bytes per second over the sampled range
+    pub throughput: f64,
+}
+
+/// Fetches the first `sample` bytes of `url` with a Range request and times it.
+pub fn speed_test(url: &str, sample: u64) -> Result<Speed, String> {
+    let start = Instant::now();
+    let mut resp = client()
+        .get(url)
+        .timeout(Duration::from_secs(20))
+        .header(RANGE, format!("bytes=0-{}", sample - 1))
+        .send()
+        .map_err(|e| format!("network error: {e}"))?;
+    let latency = start.elapsed();
+    if !resp.status().is_success() {
+        return Err(format!("server answered {}", resp.status()));
+    }
+    // a server ignoring Range would send everything; stop at `sample`
+    let got = io::copy(&mut (&mut resp).take(sample), &mut io::sink()).map_err(|e| e.to_string())?;
+    let body = start.elapsed().saturating_sub(latency).as_secs_f64().max(1e-3);
+    Ok(Speed { latency, throughput: got as f64 / body })
+}
+
+/// Download rate averaged over the last second of progress samples, so it
+/// follows bandwidth changes instead of settling on the overall average.
+#[derive(Default)]
+pub struct RateMeter {
+    /// (time since the download started, bytes so far), oldest first
+    samples: VecDeque<(Duration, u64)>,
+}
+
+impl RateMeter {
+    const WINDOW: Duration = Duration::from_secs(1);
+
+    pub fn push(&mut self, elapsed: Duration, done: u64) {
+        self.samples.push_back((elapsed, done));
+        // keep one sample at or before the window start to measure from
+        while self.samples.len() > 2 && elapsed.saturating_sub(self.samples[1].0) >= Self::WINDOW {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Bytes per second, once there are two samples apart in time.
+    pub fn per_second(&self) -> Option<f64> {
+        let (&(t0, b0), &(t1, b1)) = (self.samples.front()?, self.samples.back()?);
+        let secs = t1.saturating_sub(t0).as_secs_f64();
+        (secs > 0.0).then(|| b1.saturating_sub(b0) as f64 / secs)
+    }
+
+    /// Time left for `remaining` bytes at the current rate.
+    pub fn eta(&self, remaining: u64) -> Option<Duration> {
+        self.per_second().filter(|&r| r > 0.0).map(|r| Duration::from_secs_f64(remaining as f64 / r))
+    }
+}
+
+/// Keeps downloads under `limit` bytes/s by sleeping between chunks. The
+/// rate is measured over a window restarted every second, so a stall does
+/// not buy a burst afterwards. One is shared by every transfer (see
+/// `throttle`), so the cap holds for all of them together.
+struct Throttle {
+    limit:  u64,
+    window: Instant,
+    bytes:  u64,
+}
+
+impl Throttle {
+    const WINDOW: Duration = Duration::from_secs(1);
+
+    fn new(limit: u64) -> Self {
+        Self { limit, window: Instant::now(), bytes: 0 }
+    }
+
+    /// How long to pause after `n` more bytes, `elapsed` into the window.
+    fn delay(&mut self, n: u64, elapsed: Duration) -> Duration {
+        self.bytes += n;
+        let due = Duration::from_secs_f64(self.bytes as f64 / self.limit as f64);
+        due.saturating_sub(elapsed)
+    }
+
+    /// Counts `n` more bytes against the window; returns the pause they need.
+    fn reserve(&mut self, n: u64) -> Duration {
+        if self.window.elapsed() >= Self::WINDOW {
+            self.window = Instant::now();
+            self.bytes = 0;
+        }
+        self.delay(n, self.window.elapsed())
+    }
+}
+
+/// Paces `n` more bytes under `limit` bytes/s, counted together with every
+/// other running transfer.
+fn throttle(limit: u64, n: u64) {
+    static SHARED: OnceLock<Mutex<Throttle>> = OnceLock::new();
+    let wait = {
+        let mut t = SHARED.get_or_init(|| Mutex::new(Throttle::new(limit))).lock().unwrap_or_else(|p| p.into_inner());
+        t.limit = limit;
+        t.reserve(n)
+    };
+    if !wait.is_zero() {
+        thread::sleep(wait);
+    }
+}
+
+/// Streams `url` into `dest`, calling `on_progress(bytes_so_far, content_length)`
+/// after every chunk. An existing `dest` is treated as a partial download and
+/// continued with a Range request (restarted if the server ignores it).
+/// Checks the HTTP status and the final size; when `cancel` is raised the
+/// partial file is removed. The speed cap from the settings applies to all
+/// downloads together. A
+/// connection that fails or breaks off mid-body is retried like `send`
+/// does, with `on_retry`, continuing from what arrived; a cancel during the
+/// pause before a retry ends it. Returns the size of the finished file.
+pub fn download_to_file(
+    url: &str,
+    dest: &Path,
+    cancel: &AtomicBool,
+    mut on_progress: impl FnMut(u64, Option<u64>),
+    on_retry: &mut dyn FnMut(u32),
+) -> Result<u64, DownloadError> {
+    let limit = Config::load().download_limit();
+    retry_with(
+        || download_once(url, dest, cancel, &mut on_progress, limit),
+        DownloadError::transient,
+        on_retry,
+        |d| pause(d, cancel),
+    )
+}
+
+/// What a download reports while it runs; the game install adds the
+/// verify and extract steps after the transfer.
+pub enum ProgressEvent {
+    Progress(f32), // 0.0‒1.0
+    /// bytes so far when the server sent no Content-Length
+    Bytes(u64),
+    /// sent with every chunk: bytes so far, full size if known, and time
+    /// since the download started, for the speed and ETA readout
+    Transfer { done: u64, total: Option<u64>, elapsed: Duration },
+    /// the connection failed; retry n of `RETRIES` follows after a pause
+    Retrying(u32),
+    /// no checksum is published for this build; it installs unverified
+    Unverified,
+    Error(String),
+    Cancelled,
+    Finished,
+}
+
+/// Streams `url` into `dest` with `download_to_file`, reporting as
+/// `ProgressEvent`s: the fraction done (bytes when the size is unknown) and
+/// the transfer figures for speed and ETA.
+pub fn download_file_with_progress(
+    url: &str,
+    dest: &Path,
+    cancel: &AtomicBool,
+    emit: &mut dyn FnMut(ProgressEvent),
+) -> Result<u64, DownloadError> {
+    let start = Instant::now();
+    let emit = std::cell::RefCell::new(emit);
+    download_to_file(
+        url,
+        dest,
+        cancel,
+        |done, total| {
+            let total = total.filter(|t| *t > 0);
+            let mut emit = emit.borrow_mut();
+            match total {
+                Some(total) => emit(ProgressEvent::Progress(done as f32 / total as f32)),
+                None => emit(ProgressEvent::Bytes(done)),
+            }
+            emit(ProgressEvent::Transfer { done, total, elapsed: start.elapsed() });
+        },
+        &mut |n| emit.borrow_mut()(ProgressEvent::Retrying(n)),
+    )
+}
+
+/// Sleeps for `d`, waking early once `cancel` is raised.
+fn pause(d: Duration, cancel: &AtomicBool) {
+    let until = Instant::now() + d;
+    while !cancel.load(Ordering::Relaxed) {
+        let left = until.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            break;
+        }
+        thread::sleep(left.min(Duration::from_millis(100)));
+    }
+}
+
+/// One request of `download_to_file`, from wherever `dest` left off.
+fn download_once(
+    url: &str,
+    dest: &Path,
+    cancel: &AtomicBool,
+    on_progress: &mut impl FnMut(u64, Option<u64>),
+    limit: Option<u64>,
+) -> Result<u64, DownloadError> {
+    if cancel.load(Ordering::Relaxed) {
+        let _ = fs::remove_file(dest);
+        return Err(DownloadError::Cancelled);
+    }
+    // a partial file from an earlier run is continued where the server allows it
+    let have = fs::metadata(dest).map(|m| m.len()).unwrap_or(0);
+    let mut req = client().get(url);
+    if have > 0 {
+        req = req.header(RANGE, format!("bytes={have}-"));
+    }
+    let mut resp = req.send()?;
+    if have > 0 && resp.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+        return Ok(have); // nothing left to fetch
+    }
+    if !resp.status().is_success() {
+        return Err(DownloadError::Status(resp.status()));
+    }
+    let offset = if resp.status() == StatusCode::PARTIAL_CONTENT { have } else { 0 };
+    let total = resp.content_length().map(|n| n + offset);
+
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut dst = if offset > 0 {
+        fs::OpenOptions::new().append(true).open(dest)?
+    } else {
+        fs::File::create(dest)?
+    };
+
+    let mut downloaded = offset;
+    let mut buf = [0u8; 8192];
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            drop(dst);
+            let _ = fs::remove_file(dest);
+            return Err(DownloadError::Cancelled);
+        }
+        let n = resp.read(&mut buf).map_err(DownloadError::Dropped)?;
+        if n == 0 {
+            break;
+        }
+        dst.write_all(&buf[..n])?;
+        downloaded += n as u64;
+        on_progress(downloaded, total);
+        if let Some(limit) = limit {
+            throttle(limit, n as u64);
+        }
+    }
+    dst.flush()?;
+
+    if let Some(expected) = total {
+        if downloaded != expected {
+            return Err(DownloadError::SizeMismatch { expected, got: downloaded });
+        }
+    }
+    Ok(downloaded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil;
+
+    #[test]
+    fn rate_follows_the_last_second() {
+        let mut meter = RateMeter::default();
+        meter.push(Duration::ZERO, 0);
+        assert_eq!(meter.per_second(), None);
+        // 10 KB/s for two seconds, then 1 KB/s
+        for ms in (250..=2000).step_by(250) {
+            meter.push(Duration::from_millis(ms), ms * 10);
+        }
+        assert_eq!(meter.per_second(), Some(10_000.0));
+        for ms in (2250..=3000).step_by(250) {
+            meter.push(Duration::from_millis(ms), 20_000 + (ms - 2000));
+        }
+        assert_eq!(meter.per_second(), Some(1_000.0));
+        assert_eq!(meter.eta(5_000), Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn transient_failures_are_retried_with_backoff() {
+        let (mut calls, mut retries, waits) = (0, Vec::new(), std::cell::RefCell::new(Vec::new()));
+        let result: Result<u32, &str> = retry_with(
+            || {
+                calls += 1;
+                if calls < 3 { Err("timeout") } else { Ok(calls) }
+            },
+            |e| *e == "timeout",
+            &mut |n| retries.push(n),
+            |d| waits.borrow_mut().push(d.as_secs()),
+        );
+        assert_eq!(result, Ok(3));
+        assert_eq!(retries, [1, 2]);
+        assert_eq!(*waits.borrow(), [1, 2]);
+
+        // a lasting outage gives up after RETRIES, an answer is never retried
+        let mut calls = 0;
+        let gone: Result<(), &str> = retry_with(|| { calls += 1; Err("timeout") }, |e| *e == "timeout", &mut |_| {}, |_| {});
+        assert!(gone.is_err());
+        assert_eq!(calls, RETRIES + 1);
+        let mut calls = 0;
+        let missing: Result<(), &str> = retry_with(|| { calls += 1; Err("404") }, |e| *e == "timeout", &mut |_| {}, |_| {});
+        assert_eq!(missing, Err("404"));
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn broken_downloads_resume_where_they_stopped() {
+        // first answer promises ten bytes and hangs up after four
+        let (url, server) = testutil::serve_recorded(vec![
+            ("HTTP/1.1 200 OK\r\nContent-Length: 10\r\nConnection: close\r\n\r\n".into(), b"0123"),
+            ("HTTP/1.1 206 Partial Content\r\nContent-Length: 6\r\nConnection: close\r\n\r\n".into(), b"456789"),
+        ]);
+        let tmp = tempfile::tempdir().unwrap();
+        let dest = tmp.path().join("vs.tar.gz.part");
+        let mut retries = Vec::new();
+        let got = download_to_file(&url, &dest, &AtomicBool::new(false), |_, _| {}, &mut |n| retries.push(n));
+        assert_eq!(got.unwrap(), 10);
+        let retry = server.join().unwrap().pop().unwrap().to_lowercase();
+        assert!(retry.contains("range: bytes=4-"), "the retry asked for the rest only");
+        assert_eq!(retries, [1]);
+        assert_eq!(fs::read(&dest).unwrap(), b"0123456789");
+    }
+
+    #[test]
+    fn cancelling_ends_the_pause_before_a_retry() {
+        let cancel = AtomicBool::new(true);
+        let start = Instant::now();
+        pause(Duration::from_secs(4), &cancel);
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn throttle_waits_out_the_excess() {
+        let mut t = Throttle::new(1000);
+        assert_eq!(t.delay(500, Duration::from_millis(600)), Duration::ZERO);
+        assert_eq!(t.delay(500, Duration::from_millis(600)), Duration::from_millis(400));
+    }
+}
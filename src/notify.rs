@@ -0,0 +1,188 @@
+//! notify.rs – app-wide toasts for finished jobs and errors
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use eframe::egui;
+
+/// Successes fade out after this long; warnings and errors stay until dismissed.
+const SUCCESS_TTL: Duration = Duration::from_secs(5);
+/// Oldest notices are dropped past this many.
+const MAX_NOTICES: usize = 20;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Level {
+    Success,
+    /// it worked, with a catch the user should know about
+    Warning,
+    Error,
+}
+
+/// Follow-up a notice can offer; handled by the app, which owns the pages.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Action {
+    /// start the version download again, resuming what it got
+    RetryDownload(String),
+}
+
+struct Notice {
+    id:     u64,
+    level:  Level,
+    text:   String,
+    at:     Instant,
+    action: Option<Action>,
+}
+
+#[derive(Default)]
+struct Inner {
+    next_id: u64,
+    notices: Vec<Notice>,
+}
+
+impl Inner {
+    /// Drops successes older than `SUCCESS_TTL`.
+    fn expire(&mut self, now: Instant) {
+        self.notices
+            .retain(|n| n.level != Level::Success || now.duration_since(n.at) < SUCCESS_TTL);
+    }
+}
+
+/// Shared notification list. Clone freely; pages push, the app draws.
+#[derive(Clone, Default)]
+pub struct Notifier(Arc<Mutex<Inner>>);
+
+impl Notifier {
+    pub fn success(&self, text: impl Into<String>) {
+        self.push(Level::Success, text.into(), None);
+    }
+
+    pub fn warning(&self, text: impl Into<String>) {
+        self.push(Level::Warning, text.into(), None);
+    }
+
+    pub fn error(&self, text: impl Into<String>) {
+        self.push(Level::Error, text.into(), None);
+    }
+
+    /// An error with a button that fires `action`.
+    pub fn error_with(&self, text: impl Into<String>, action: Action) {
+        self.push(Level::Error, text.into(), Some(action));
+    }
+
+    /// `Ok` as a success, `Err` as an error.
+    pub fn result(&self, result: Result<String, String>) {
+        match result {
+            Ok(msg) => self.success(msg),
+            Err(msg) => self.error(msg),
+        }
+    }
+
+    fn push(&self, level: Level, text: String, action: Option<Action>) {
+        let mut inner = self.0.lock().unwrap_or_else(|p| p.into_inner());
+        let id = inner.next_id;
+        inner.next_id += 1;
+        inner.notices.push(Notice { id, level, text, at: Instant::now(), action });
+        let over = inner.notices.len().saturating_sub(MAX_NOTICES);
+        inner.notices.drain(..over);
+    }
+
+    /// Draws the toasts bottom-right, newest at the bottom. Returns the
+    /// action whose button was clicked this frame.
+    pub fn ui(&self, ctx: &egui::Context) -> Option<Action> {
+        let mut inner = self.0.lock().unwrap_or_else(|p| p.into_inner());
+        let now = Instant::now();
+        inner.expire(now);
+        if inner.notices.is_empty() {
+            return None;
+        }
+        if inner.notices.iter().any(|n| n.level == Level::Success) {
+            ctx.request_repaint_after(Duration::from_millis(500));
+        }
+
+        let (mut fired, mut dismiss, mut clear) = (None, None, false);
+        egui::Area::new(egui::Id::new("notifications"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, [-8.0, -40.0])
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                ui.set_max_width(360.0);
+                if inner.notices.len() > 1 && ui.small_button("Clear all").clicked() {
+                    clear = true;
+                }
+                for n in &inner.notices {
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            let (icon, color) = match n.level {
+                                Level::Success => ("✔", egui::Color32::from_rgb(0x4c, 0xaf, 0x50)),
+                                Level::Warning => ("⚠", ui.visuals().warn_fg_color),
+                                Level::Error => ("✖", ui.visuals().error_fg_color),
+                            };
+                            ui.colored_label(color, icon);
+                            ui.vertical(|ui| {
+                                ui.label(&n.text);
+                                ui.weak(age(now.duration_since(n.at)));
+                            });
+                            ui.with_layout(egui::Layout::right_to_left(egui::Align::TOP), |ui| {
+                                if ui.small_button("✖").on_hover_text("Dismiss").clicked() {
+                                    dismiss = Some(n.id);
+                                }
+                                if let Some(action) = &n.action {
+                                    let label = match action {
+                                        Action::RetryDownload(_) => "Retry",
+                                    };
+                                    if ui.small_button(label).clicked() {
+                                        fired = Some(action.clone());
+                                        dismiss = Some(n.id);
+                                    }
+                                }
+                            });
+                        });
+                    });
+                }
+            });
+        if clear {
+            inner.notices.clear();
+        }
+        if let Some(id) = dismiss {
+            inner.notices.retain(|n| n.id != id);
+        }
+        fired
+    }
+}
+
+/// "just now" / "12 s ago" / "3 min ago"
+fn age(d: Duration) -> String {
+    match d.as_secs() {
+        0..=4 => "just now".into(),
+        s @ 5..=59 => format!("{s} s ago"),
+        s => format!("{} min ago", s / 60),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn successes_expire_errors_stay() {
+        let notify = Notifier::default();
+        notify.success("done");
+        notify.warning("unverified");
+        notify.error("broke");
+        let mut inner = notify.0.lock().unwrap();
+        inner.expire(Instant::now() + SUCCESS_TTL);
+        let left: Vec<_> = inner.notices.iter().map(|n| n.text.as_str()).collect();
+        assert_eq!(left, ["unverified", "broke"]);
+    }
+
+    #[test]
+    fn oldest_dropped_past_the_cap() {
+        let notify = Notifier::default();
+        for i in 0..MAX_NOTICES + 3 {
+            notify.error(i.to_string());
+        }
+        let inner = notify.0.lock().unwrap();
+        assert_eq!(inner.notices.len(), MAX_NOTICES);
+        assert_eq!(inner.notices[0].text, "3");
+    }
+}
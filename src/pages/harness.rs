@@ -0,0 +1,34 @@
+//! pages/harness.rs – headless egui frames for page tests
+//!
+//! Drives a page's `ui` through a bare `egui::Context`: no window, no
+//! renderer. Feed input events, collect what the page returned.
+use eframe::egui::{self, Event, Key, Modifiers, RawInput};
+
+/// A context with a fixed screen, ready for `frame`.
+pub(crate) fn context() -> egui::Context {
+    let ctx = egui::Context::default();
+    frame(&ctx, Vec::new(), |_| ()); // first frame lays out nothing useful
+    ctx
+}
+
+/// Runs one frame with `events` as the input and returns what `ui` returned.
+pub(crate) fn frame<R>(ctx: &egui::Context, events: Vec<Event>, ui: impl FnOnce(&egui::Context) -> R) -> R {
+    let input = RawInput {
+        screen_rect: Some(egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(1024.0, 768.0))),
+        events,
+        ..Default::default()
+    };
+    let mut out = None;
+    let _ = ctx.run(input, |ctx| out = Some(ui(ctx)));
+    out.expect("egui ran the frame")
+}
+
+/// A single key press.
+pub(crate) fn press(key: Key) -> Event {
+    press_with(key, Modifiers::NONE)
+}
+
+/// A key press with modifiers held.
+pub(crate) fn press_with(key: Key, modifiers: Modifiers) -> Event {
+    Event::Key { key, physical_key: None, pressed: true, repeat: false, modifiers }
+}
@@ -1,21 +1,136 @@
 //! pages/instances.rs – create / list / delete instances
-use std::{fs, path::PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    fs, io,
+    path::{Path, PathBuf},
+};
 
-use dirs::data_local_dir;
 use eframe::egui::{self, CentralPanel};
 use serde::{Deserialize, Serialize};
 
-use crate::pages::versions::VersionPage;
+use crate::{
+    activity::{Activity, TaskId, TryRecvError},
+    config::Config,
+    disk, launch,
+    modhistory::{self, Change, Entry, ModHistory},
+    modinfo::{self, InstalledMod},
+    modlock::ModLock,
+    notify::Notifier,
+    paths,
+    pages::{
+        fuzzy_filter, list_keys,
+        versions::{Fetch, VersionPage},
+        ListKey,
+    },
+    preflight::{self, Check},
+    shortcut,
+    textures::{self, TextureCache},
+    worlds::{self, WorldVersions},
+};
+
+/// What a template contributes to a new instance: settings and keybinds,
+/// never worlds, logs or mods.
+const TEMPLATE_ITEMS: &[&str] = &["clientsettings.json", "ModConfig", "Macros"];
+
+/// Icons are downscaled to this edge length when imported.
+const ICON_SIZE: u32 = 128;
 
 /*──────────────────── data ───────────────────*/
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Instance {
+    /// stable identity; survives reordering (and renames)
+    #[serde(default)]
+    pub id:      u64,
     pub name:    String,
     pub version: String,
+    /// external mods folder (e.g. a shared library); `None` = `<instance>/mods`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mods_path_override: Option<PathBuf>,
+    /// thumbnail, normally `<instance>/icon.png`; `None` = lettered placeholder
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<PathBuf>,
+    /// free-form reminder of what the instance is for
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub notes: String,
+    /// starred: listed ahead of the others here and in the footer
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub favorite: bool,
+    /// binary to run instead of the managed install's (dev builds, patched clients)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_executable: Option<PathBuf>,
+    /// data folder of an adopted install, used in place and never deleted;
+    /// `None` = managed under `instances/<name>`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub external_data: Option<PathBuf>,
+    /// custom parent of the managed data folder, fixed when the instance was
+    /// created; `None` = the default `instances/`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_root: Option<PathBuf>,
+    /// update unpinned mods to the newest compatible release before each launch
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub auto_update_mods: bool,
+    /// unix seconds of the last successful launch; `None` = never
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_played: Option<u64>,
+}
+
+impl Instance {
+    /// per-instance folder handed to the game as `--dataPath`
+    pub fn data_dir(&self) -> PathBuf {
+        self.external_data
+            .clone()
+            .unwrap_or_else(|| paths::instance_dir(self.data_root.as_deref(), &self.name))
+    }
+    /// worlds the game writes under the data path
+    pub fn saves_dir(&self) -> PathBuf {
+        self.data_dir().join("Saves")
+    }
+    /// `.vcdbs` saves in `saves_dir`, most recently played first
+    pub fn worlds(&self) -> Vec<PathBuf> {
+        let mut worlds: Vec<(std::time::SystemTime, PathBuf)> = fs::read_dir(self.saves_dir())
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|x| x == "vcdbs"))
+            .map(|p| (fs::metadata(&p).and_then(|m| m.modified()).unwrap_or(std::time::UNIX_EPOCH), p))
+            .collect();
+        worlds.sort_by_key(|w| std::cmp::Reverse(w.0));
+        worlds.into_iter().map(|(_, p)| p).collect()
+    }
+    /// client/server logs and crash reports
+    pub fn logs_dir(&self) -> PathBuf {
+        self.data_dir().join("Logs")
+    }
+    /// where mods for this instance live (install target + `--addModPath`)
+    pub fn mods_dir(&self) -> PathBuf {
+        self.mods_path_override
+            .clone()
+            .unwrap_or_else(|| self.data_dir().join("mods"))
+    }
+}
+
+/// "Import existing install…" form.
+#[derive(Default)]
+struct ImportForm {
+    name:     String,
+    version:  String,
+    /// folder holding the game binary
+    game_dir: String,
+    /// the install's `--dataPath` (worlds, settings, Mods)
+    data_dir: String,
 }
 
 pub enum InstanceCmd {
-    Play(usize),
+    Play(u64),
+    /// launch straight into a save from the instance's Saves folder
+    PlayWorld { id: u64, world: PathBuf },
+    /// launch and open the live log window
+    PlayWatchLog(u64),
+    /// open the log of the instance's most recent launch
+    ShowLog(u64),
+    /// fetch a game version the create dialog is waiting on
+    Download(String),
     None,
 }
 
@@ -23,47 +138,211 @@ pub struct InstancesPage {
     pub instances: Vec<Instance>,
     new_name:      String,
     new_version:   String,
+    new_mods_path: String,
+    /// instance name (or folder path) whose config seeds the new instance
+    new_template:  Option<String>,
+    new_notes:     String,
     show_modal:    bool,
-    pub status_msg: Option<String>,
-    pending_delete: Option<usize>,
+    /// instance the dialog edits; `None` = it creates a new one
+    editing:       Option<u64>,
+    /// version the create dialog is downloading; the instance is created once it lands
+    awaiting_version: Option<String>,
+    /// instance waiting for the delete confirmation
+    pending_delete: Option<u64>,
+    deleting:       Vec<(u64, TaskId)>,
+    /// duplicates whose files are still being copied, with the folders
+    /// the copy creates (removed again if it fails)
+    copying:        Vec<(u64, TaskId, Vec<PathBuf>)>,
+    /* mods listed per instance id, read in the background on first show
+       (or when the section is opened first) */
+    mod_lists:      HashMap<u64, Vec<InstalledMod>>,
+    mods_scan:      Option<TaskId>,
+    /// instances whose mods changed while a scan was running; its
+    /// (possibly older) reading of them is dropped
+    mods_rescan:    HashSet<u64>,
+    /// modlock.json per instance id, loaded alongside the mod list
+    mod_locks:      HashMap<u64, ModLock>,
+    pending_mod_remove: Option<(u64, PathBuf)>,
+    /// instance_history.json per instance id, read when the section is opened
+    histories:      HashMap<u64, ModHistory>,
+    pending_restore: Option<(u64, Entry)>,
+    pending_move:   Option<(usize, usize)>,
+    /// "Set icon…" dialog: instance id + source path being typed
+    icon_dialog:    Option<(u64, String)>,
+    /// "Edit notes…" dialog: instance id + text being edited
+    notes_dialog:   Option<(u64, String)>,
+    /// "Custom executable…" dialog: instance id + path being typed
+    exe_dialog:     Option<(u64, String)>,
+    import_dialog:  Option<ImportForm>,
+    /* "Validate" report: running check, then the result for one instance */
+    validation_task: Option<(u64, TaskId)>,
+    validation:     Option<(u64, Vec<Check>)>,
+    pub icons:      TextureCache,
+    /// keyboard highlight, index into the shown (filtered) cards
+    selected_row:   Option<usize>,
+    search:         String,
+    /// order cards (and the footer) by last launch instead of list order
+    sort_recent:    bool,
+    /// id the next new instance gets; kept in instances.json so a deleted
+    /// instance's id is never handed out again
+    next_id:        u64,
+}
+
+/// instances.json: the list plus the id counter. Files from before the
+/// counter are a bare list and read as one with the counter unset.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum InstancesFile {
+    Counted {
+        #[serde(default)]
+        next_id:   u64,
+        instances: Vec<Instance>,
+    },
+    Bare(Vec<Instance>),
+}
+
+impl InstancesFile {
+    fn read() -> Option<Self> {
+        let txt = std::fs::read_to_string(paths::instances_file()).ok()?;
+        serde_json::from_str(&txt).ok()
+    }
+    fn into_parts(self) -> (u64, Vec<Instance>) {
+        match self {
+            InstancesFile::Counted { next_id, instances } => (next_id, instances),
+            InstancesFile::Bare(instances) => (0, instances),
+        }
+    }
 }
 
 impl Default for InstancesPage {
     fn default() -> Self {
+        let (next_id, instances) = InstancesFile::read().map(InstancesFile::into_parts).unwrap_or_default();
+        let mut page = Self::with_instances(instances);
+        page.next_id = page.next_id.max(next_id);
+        // files written before ids existed get them once, here
+        if page.instances.iter().any(|i| i.id == 0) {
+            page.assign_missing_ids();
+            page.save_instances();
+        }
+        page
+    }
+}
+
+impl InstancesPage {
+    /// Page over `instances` as given; `Default` loads them from disk.
+    fn with_instances(instances: Vec<Instance>) -> Self {
+        let next_id = instances.iter().map(|i| i.id).max().unwrap_or(0) + 1;
         Self {
-            instances: Self::load_instances(),
+            instances,
             new_name: String::new(),
             new_version: String::new(),
+            new_mods_path: String::new(),
+            new_template: None,
+            new_notes: String::new(),
             show_modal: false,
-            status_msg: None,
+            editing: None,
+            awaiting_version: None,
             pending_delete: None,
+            deleting: Vec::new(),
+            copying: Vec::new(),
+            mod_lists: HashMap::new(),
+            mods_scan: None,
+            mods_rescan: HashSet::new(),
+            mod_locks: HashMap::new(),
+            pending_mod_remove: None,
+            histories: HashMap::new(),
+            pending_restore: None,
+            pending_move: None,
+            icon_dialog: None,
+            notes_dialog: None,
+            exe_dialog: None,
+            import_dialog: None,
+            validation_task: None,
+            validation: None,
+            icons: TextureCache::default(),
+            selected_row: None,
+            search: String::new(),
+            sort_recent: false,
+            next_id,
         }
     }
 }
 
 /*────────────────── disk helpers ─────────────*/
 impl InstancesPage {
-    fn instances_file() -> PathBuf {
-        data_local_dir()
-            .unwrap_or_else(|| PathBuf::from("~/.local/share"))
-            .join("vs_launcher/instances.json")
+    pub(crate) fn load_instances() -> Vec<Instance> {
+        InstancesFile::read().map(|f| f.into_parts().1).unwrap_or_default()
+    }
+    /// Looks an instance up by its stable id.
+    pub fn get(&self, id: u64) -> Option<&Instance> {
+        self.instances.iter().find(|i| i.id == id)
+    }
+    /// Unmet mod dependencies of instance `id`, one message each. Reads
+    /// its mods folder first when no list is cached (an instance added
+    /// since the last scan), so a launch is never waved through unchecked.
+    pub fn dependency_problems(&mut self, id: u64) -> Vec<String> {
+        let Some(inst) = self.instances.iter().find(|i| i.id == id) else { return Vec::new() };
+        let mods = self.mod_lists.entry(id).or_insert_with(|| modinfo::list_mods(&inst.mods_dir()));
+        modinfo::check_dependencies(mods, &inst.version)
+    }
+    /// Drops the cached mod list of instance `id` after something outside
+    /// this page installed or updated its mods; the next frame reads it again.
+    pub fn mods_changed(&mut self, id: u64) {
+        self.mod_lists.remove(&id);
+        if self.mods_scan.is_some() {
+            self.mods_rescan.insert(id);
+        }
+    }
+    /// Whether a card is highlighted, so Enter is the list's to handle.
+    pub fn consumes_enter(&self) -> bool {
+        self.selected_row.is_some()
+    }
+    /// Stamps instance `id` as played now and saves.
+    pub fn mark_played(&mut self, id: u64) {
+        if let Some(inst) = self.instances.iter_mut().find(|i| i.id == id) {
+            inst.last_played = Some(modhistory::now());
+            self.save_instances();
+        }
+    }
+    /// Names of the instances set to game version `ver`.
+    pub fn instances_using(&self, ver: &str) -> Vec<String> {
+        self.instances.iter().filter(|i| i.version == ver).map(|i| i.name.clone()).collect()
     }
-    fn load_instances() -> Vec<Instance> {
-        std::fs::read_to_string(Self::instances_file())
-            .ok()
-            .and_then(|txt| serde_json::from_str(&txt).ok())
-            .unwrap_or_default()
+    /// Indices matching `query` (fuzzy-ranked, or most recently played
+    /// first when that sort is on), favorites first.
+    pub fn ordered(&self, query: &str) -> Vec<usize> {
+        let mut shown = fuzzy_filter(query, &self.instances, |i| i.name.clone());
+        if self.sort_recent {
+            shown.sort_by_key(|&i| std::cmp::Reverse(self.instances[i].last_played));
+        }
+        shown.sort_by_key(|&i| !self.instances[i].favorite); // stable: keeps rank/order within each group
+        shown
+    }
+    /// Hands out a fresh id: past every id in use and every id given before.
+    fn next_id(&mut self) -> u64 {
+        let id = self.instances.iter().map(|i| i.id + 1).fold(self.next_id, u64::max);
+        self.next_id = id + 1;
+        id
+    }
+    fn assign_missing_ids(&mut self) {
+        for idx in 0..self.instances.len() {
+            if self.instances[idx].id == 0 {
+                self.instances[idx].id = self.next_id();
+            }
+        }
     }
     fn save_instances(&self) {
-        let path = Self::instances_file();
+        let path = paths::instances_file();
         if let Some(p) = path.parent() { let _ = fs::create_dir_all(p); }
-        if let Ok(j) = serde_json::to_string_pretty(&self.instances) {
+        let file = serde_json::json!({ "next_id": self.next_id, "instances": &self.instances });
+        if let Ok(j) = serde_json::to_string_pretty(&file) {
             let _ = std::fs::write(path, j);
         }
     }
-    fn installed_versions() -> Vec<String> {
-        let root = VersionPage::versions_dir();
-        let mut v = Vec::new();
+    /// Game versions with a finished install, newest first.
+    pub(crate) fn installed_versions() -> Vec<String> {
+        let root = paths::versions_dir();
+        let mut v: Vec<String> = Vec::new();
         if let Ok(rd) = fs::read_dir(root) {
             for e in rd.flatten() {
                 if e.path().join("install").exists() {
@@ -71,121 +350,1655 @@ impl InstancesPage {
                 }
             }
         }
-        v.sort();
+        v.sort_by(|a, b| VersionPage::version_cmp(b, a)); // newest first
         v
     }
-    fn remove_instance(&mut self, idx: usize) {
-        if let Some(inst) = self.instances.get(idx) {
-            let folder = data_local_dir()
-                .unwrap_or_else(|| PathBuf::from("~/.local/share"))
-                .join("vs_launcher/instances")
-                .join(&inst.name);
-            if let Err(e) = fs::remove_dir_all(&folder) {
-                self.status_msg = Some(format!("Delete error: {e}"));
-                return;
+    /// Starts removing the instance folder on a worker; `poll_deletes` finishes up.
+    fn remove_instance(&mut self, id: u64, activity: &Activity, notify: &Notifier) {
+        let Some(inst) = self.get(id) else { return };
+        if self.deleting.iter().any(|(d, _)| *d == id) {
+            return;
+        }
+        if inst.external_data.is_some() {
+            // adopted install: forget it, leave its folders alone
+            notify.success(format!("Removed {} from the launcher; its game and data folders were kept", inst.name));
+            self.forget(id);
+            return;
+        }
+        if let Some(other) = self.sharing_data(inst) {
+            // deleting the folder would take the other instance's saves with it
+            notify.success(format!("Removed {}; its data folder is also {other}'s and was kept", inst.name));
+            self.forget(id);
+            return;
+        }
+        // with a custom root the launcher's own folder (the icon) is separate
+        let mut dirs = vec![inst.data_dir()];
+        let own = paths::instance_dir(None, &inst.name);
+        if own != dirs[0] && !self.instances.iter().any(|i| i.id != id && i.name == inst.name) {
+            dirs.push(own);
+        }
+        let task = disk::remove_dirs_in_background(dirs, format!("Deleting {}", inst.name), activity);
+        self.deleting.push((id, task));
+    }
+
+    /// Name of another instance whose data folder is `inst`'s.
+    fn sharing_data(&self, inst: &Instance) -> Option<&str> {
+        let dir = inst.data_dir();
+        self.instances.iter().find(|i| i.id != inst.id && i.data_dir() == dir).map(|i| i.name.as_str())
+    }
+
+    /// Drops instance `id` from the list and its caches, and saves.
+    fn forget(&mut self, id: u64) {
+        self.instances.retain(|i| i.id != id);
+        self.mod_lists.remove(&id);
+        self.mod_locks.remove(&id);
+        self.histories.remove(&id);
+        self.save_instances();
+    }
+
+    /// "<name> (copy)", or "<name> (copy 2)", … – the first one no
+    /// instance uses yet and whose data folder (under `root`) and icon
+    /// folder don't exist, so the copy never writes into someone's files.
+    fn copy_name(&self, name: &str, root: Option<&Path>) -> String {
+        (1..)
+            .map(|n| if n == 1 { format!("{name} (copy)") } else { format!("{name} (copy {n})") })
+            .find(|candidate| {
+                !self.instances.iter().any(|i| &i.name == candidate)
+                    && !paths::instance_dir(root, candidate).exists()
+                    && !paths::instance_dir(None, candidate).exists()
+            })
+            .unwrap()
+    }
+
+    /// Adds a copy of instance `id` right away and copies its data folder
+    /// (plus a mods folder kept elsewhere) into the copy's own folder on a
+    /// worker; `poll_copies` finishes up. The copy is always a managed
+    /// instance, so experimenting with it never touches the original's files.
+    fn duplicate_instance(&mut self, id: u64, activity: &Activity) {
+        let Some(src) = self.get(id).cloned() else { return };
+        let mut copy = src.clone();
+        copy.id = self.next_id();
+        copy.data_root = Config::load().instances_root();
+        copy.name = self.copy_name(&src.name, copy.data_root.as_deref());
+        copy.favorite = false;
+        copy.last_played = None;
+        copy.external_data = None;
+        copy.mods_path_override = None;
+
+        let mut pairs = vec![(src.data_dir(), copy.data_dir())];
+        if src.mods_path_override.is_some() {
+            pairs.push((src.mods_dir(), copy.mods_dir()));
+        }
+        // icons set in the launcher live in the instance's own folder
+        if let Some(icon) = &src.icon {
+            if let Ok(rest) = icon.strip_prefix(paths::instance_dir(None, &src.name)) {
+                let dest = paths::instance_dir(None, &copy.name).join(rest);
+                pairs.push((icon.clone(), dest.clone()));
+                copy.icon = Some(dest);
             }
         }
-        self.instances.remove(idx);
+        // both are free (see `copy_name`), so whatever is in them came from this copy
+        let mut created = vec![copy.data_dir()];
+        let icons = paths::instance_dir(None, &copy.name);
+        if icons != created[0] {
+            created.push(icons);
+        }
+        let task = disk::copy_in_background(pairs, format!("Copying {} to {}", src.name, copy.name), activity);
+        self.copying.push((copy.id, task, created));
+        self.instances.push(copy);
         self.save_instances();
-        self.status_msg = Some("Instance deleted".into());
+    }
+
+    /// Reads the mods of every instance without a cached list on a worker,
+    /// so the cards can warn about dependencies without the Mods section
+    /// being opened. New instances and dropped lists are picked up the same way.
+    fn scan_mods(&mut self, activity: &Activity) {
+        let dirs: Vec<(u64, PathBuf)> = self
+            .instances
+            .iter()
+            .filter(|i| !self.mod_lists.contains_key(&i.id))
+            .map(|i| (i.id, i.mods_dir()))
+            .collect();
+        if self.mods_scan.is_none() && !dirs.is_empty() {
+            self.mods_scan = Some(activity.spawn("Reading installed mods", move |task| {
+                for (id, dir) in dirs {
+                    task.post((id, modinfo::list_mods(&dir)));
+                }
+            }));
+        }
+        let Some(task) = self.mods_scan else { return };
+        loop {
+            match activity.try_take::<(u64, Vec<InstalledMod>)>(task) {
+                // a list read since (section opened, mod removed) is newer
+                Ok((id, list)) => {
+                    if !self.mods_rescan.remove(&id) {
+                        self.mod_lists.entry(id).or_insert(list);
+                    }
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.mods_scan = None;
+                    self.mods_rescan.clear();
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Finishes duplicates whose copy is done; a failed copy is removed
+    /// again, with the folders it created and nothing else.
+    fn poll_copies(&mut self, activity: &Activity, notify: &Notifier) {
+        let mut finished = Vec::new();
+        self.copying.retain_mut(|(id, task, created)| match activity.try_take(*task) {
+            Ok(res) => {
+                finished.push((*id, res, std::mem::take(created)));
+                false
+            }
+            Err(TryRecvError::Empty) => true,
+            Err(TryRecvError::Disconnected) => {
+                finished.push((*id, Err(io::Error::other("worker stopped")), std::mem::take(created)));
+                false
+            }
+        });
+        for (id, res, created) in finished {
+            let Some(inst) = self.get(id).cloned() else { continue };
+            match res {
+                Ok(()) => notify.success(format!("Created {}", inst.name)),
+                Err(e) => {
+                    notify.error(format!("Could not copy into {}: {e}", inst.name));
+                    for dir in created {
+                        let _ = fs::remove_dir_all(dir);
+                    }
+                    self.instances.retain(|i| i.id != id);
+                    self.save_instances();
+                }
+            }
+        }
+    }
+
+    /// Moves the card at `from` to `to` and persists the new order. Only
+    /// within the favorites or within the rest: favorites are always shown
+    /// first, so a move across would not land where it was dropped.
+    fn move_instance(&mut self, from: usize, to: usize) {
+        if from == to || !self.same_group(from, to) {
+            return;
+        }
+        let inst = self.instances.remove(from);
+        self.instances.insert(to, inst);
+        self.save_instances();
+    }
+
+    /// Whether the cards at `a` and `b` are both favorites or both not.
+    fn same_group(&self, a: usize, b: usize) -> bool {
+        match (self.instances.get(a), self.instances.get(b)) {
+            (Some(a), Some(b)) => a.favorite == b.favorite,
+            _ => false,
+        }
+    }
+
+    /// Copies `src` (PNG/JPEG) to `<instance>/icon.png`, downscaled, and records it.
+    fn set_icon(&mut self, id: u64, src: &Path) -> Result<(), String> {
+        let Some(idx) = self.instances.iter().position(|i| i.id == id) else {
+            return Err("No such instance".into());
+        };
+        let img = image::open(src).map_err(|e| format!("Cannot read image: {e}"))?;
+        // kept with the launcher even when the data folder is an adopted one
+        let dest = paths::instance_dir(None, &self.instances[idx].name).join("icon.png");
+        if let Some(p) = dest.parent() { let _ = fs::create_dir_all(p); }
+        img.thumbnail(ICON_SIZE, ICON_SIZE)
+            .save_with_format(&dest, image::ImageFormat::Png)
+            .map_err(|e| format!("Cannot save icon: {e}"))?;
+        self.icons.forget(&dest);
+        self.instances[idx].icon = Some(dest);
+        self.save_instances();
+        Ok(())
+    }
+
+    fn toggle_favorite(&mut self, id: u64) {
+        let Some(inst) = self.instances.iter_mut().find(|i| i.id == id) else { return };
+        inst.favorite = !inst.favorite;
+        self.save_instances();
+    }
+
+    fn clear_icon(&mut self, id: u64) {
+        let Some(inst) = self.instances.iter_mut().find(|i| i.id == id) else { return };
+        if let Some(path) = inst.icon.take() {
+            let _ = fs::remove_file(&path);
+            self.icons.forget(&path);
+        }
+        self.save_instances();
+    }
+
+    fn poll_deletes(&mut self, activity: &Activity, notify: &Notifier) {
+        let mut finished = Vec::new();
+        self.deleting.retain(|(id, task)| match activity.try_take(*task) {
+            Ok(res) => {
+                finished.push((*id, res));
+                false
+            }
+            Err(TryRecvError::Empty) => true,
+            Err(TryRecvError::Disconnected) => {
+                finished.push((*id, Err(io::Error::other("worker stopped"))));
+                false
+            }
+        });
+        for (id, res) in finished {
+            match res {
+                Ok(()) => {
+                    self.forget(id);
+                    notify.success("Instance deleted");
+                }
+                Err(e) => notify.error(format!("Delete error: {e}")),
+            }
+        }
     }
 }
 
 /*──────────────────── UI ─────────────────────*/
 impl InstancesPage {
     /// Draws the page and returns a play-request (if any)
-    pub fn ui(&mut self, ctx: &egui::Context) -> InstanceCmd {
+    pub fn ui(
+        &mut self,
+        ctx: &egui::Context,
+        activity: &Activity,
+        notify: &Notifier,
+        versions: &VersionPage,
+    ) -> InstanceCmd {
         let mut cmd = InstanceCmd::None;
+        self.poll_deletes(activity, notify);
+        self.poll_copies(activity, notify);
+        self.scan_mods(activity);
+        self.poll_awaited_version(versions, notify);
 
         CentralPanel::default().show(ctx, |ui| {
             ui.heading("Instances");
-            if let Some(msg) = &self.status_msg { ui.label(msg); }
+            ui.horizontal(|ui| {
+                ui.label("Search:");
+                ui.text_edit_singleline(&mut self.search);
+                ui.toggle_value(&mut self.sort_recent, "🕑 Recently played")
+                    .on_hover_text("Order by last launch instead of list order");
+            });
 
             /* list ------------------------------------------------ */
-            self.pending_delete = None;
+            let mut clear_icon = None;
+            let mut validate = None;
+            let mut star = None;
+            let mut auto_update = None;
+            let mut edit = None;
+            let mut duplicate = None;
+            // reordering only makes sense on the full, unranked list
+            let searching = !self.search.trim().is_empty() || self.sort_recent;
+            let shown = self.ordered(&self.search);
+            let key = list_keys(ctx, shown.len(), &mut self.selected_row);
+            if key == ListKey::Activate {
+                if let Some(inst) = self.selected_row.map(|row| &self.instances[shown[row]]) {
+                    cmd = InstanceCmd::Play(inst.id);
+                }
+            }
 
-            for (idx, inst) in self.instances.iter().enumerate() {
-                ui.group(|ui| {
+            for (row, &idx) in shown.iter().enumerate() {
+                let inst = &self.instances[idx];
+                let deleting = self.deleting.iter().any(|(id, _)| *id == inst.id);
+                let copying = self.copying.iter().any(|(id, ..)| *id == inst.id);
+                let card = ui.add_enabled_ui(!deleting && !copying, |ui| ui.group(|ui| {
                     ui.horizontal(|ui| {
+                        if !searching {
+                            ui.dnd_drag_source(egui::Id::new(("inst_drag", inst.id)), idx, |ui| {
+                                ui.label("☰");
+                            })
+                            .response
+                            .on_hover_text("Drag to reorder");
+                        }
+                        let tex = inst.icon.as_deref().and_then(|p| self.icons.file(ctx, p));
+                        textures::avatar(ui, tex, &inst.name, 48.0);
                         ui.vertical(|ui| {
-                            ui.label(egui::RichText::new(&inst.name).strong());
-                            ui.label(format!("v{}", inst.version));
+                            ui.horizontal(|ui| {
+                                let (glyph, tip) = if inst.favorite { ("★", "Unstar") } else { ("☆", "Star: list first") };
+                                if ui.small_button(glyph).on_hover_text(tip).clicked() {
+                                    star = Some(inst.id);
+                                }
+                                ui.add(
+                                    egui::Label::new(egui::RichText::new(&inst.name).strong())
+                                        .sense(egui::Sense::click()),
+                                )
+                                .on_hover_text("Right-click for more actions")
+                                .context_menu(|ui| {
+                                    if ui.button("▶ Play").clicked() {
+                                        cmd = InstanceCmd::Play(inst.id);
+                                        ui.close_menu();
+                                    }
+                                    if ui
+                                        .button("📜 Play & watch log")
+                                        .on_hover_text("Launch and follow the game's output live")
+                                        .clicked()
+                                    {
+                                        cmd = InstanceCmd::PlayWatchLog(inst.id);
+                                        ui.close_menu();
+                                    }
+                                    if ui.button("📃 Last log").on_hover_text("Output of the latest launch this session").clicked() {
+                                        cmd = InstanceCmd::ShowLog(inst.id);
+                                        ui.close_menu();
+                                    }
+                                    ui.menu_button("🌍 Play world", |ui| {
+                                        let worlds = inst.worlds();
+                                        if worlds.is_empty() {
+                                            ui.weak("No saves yet");
+                                        }
+                                        let record = WorldVersions::load(inst);
+                                        for world in worlds {
+                                            let name = world.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+                                            let warning = record
+                                                .version_of(&world)
+                                                .and_then(|v| worlds::compatibility(v, &inst.version));
+                                            let button = match &warning {
+                                                Some(w) => ui.button(format!("⚠ {name}")).on_hover_text(w),
+                                                None => ui.button(name),
+                                            };
+                                            if button.clicked() {
+                                                cmd = InstanceCmd::PlayWorld { id: inst.id, world };
+                                                ui.close_menu();
+                                            }
+                                        }
+                                    });
+                                    if ui.button("✅ Validate").clicked() {
+                                        validate = Some(inst.clone());
+                                        ui.close_menu();
+                                    }
+                                    if ui.button("📂 Open folder").clicked() {
+                                        let _ = open::that(inst.data_dir());
+                                        ui.close_menu();
+                                    }
+                                    if ui.button("🌍 Open saves").clicked() {
+                                        Self::open_dir(&inst.saves_dir());
+                                        ui.close_menu();
+                                    }
+                                    if ui.button("📜 Open game logs").clicked() {
+                                        Self::open_dir(&inst.logs_dir());
+                                        ui.close_menu();
+                                    }
+                                    if ui.button("🖥 Create desktop shortcut").clicked() {
+                                        notify.result(match shortcut::create_desktop_entry(inst) {
+                                            Ok(p) => Ok(format!("Shortcut written to {}", p.display())),
+                                            Err(e) => Err(format!("Shortcut error: {e}")),
+                                        });
+                                        ui.close_menu();
+                                    }
+                                    if ui.button("🖼 Set icon…").clicked() {
+                                        self.icon_dialog = Some((inst.id, String::new()));
+                                        ui.close_menu();
+                                    }
+                                    if ui.button("✏ Edit…").clicked() {
+                                        edit = Some(inst.id);
+                                        ui.close_menu();
+                                    }
+                                    if ui.button("⧉ Duplicate").clicked() {
+                                        duplicate = Some(inst.id);
+                                        ui.close_menu();
+                                    }
+                                    if ui.button("📝 Edit notes…").clicked() {
+                                        self.notes_dialog = Some((inst.id, inst.notes.clone()));
+                                        ui.close_menu();
+                                    }
+                                    let mut on = inst.auto_update_mods;
+                                    if ui
+                                        .checkbox(&mut on, "Auto-update mods on launch")
+                                        .on_hover_text("Pinned mods (modlock.json) are left alone")
+                                        .changed()
+                                    {
+                                        auto_update = Some((inst.id, on));
+                                        ui.close_menu();
+                                    }
+                                    if ui.button("⚙ Custom executable…").clicked() {
+                                        let current = inst
+                                            .custom_executable
+                                            .as_ref()
+                                            .map(|p| p.to_string_lossy().into_owned())
+                                            .unwrap_or_default();
+                                        self.exe_dialog = Some((inst.id, current));
+                                        ui.close_menu();
+                                    }
+                                    if inst.icon.is_some() && ui.button("Remove icon").clicked() {
+                                        clear_icon = Some(inst.id);
+                                        ui.close_menu();
+                                    }
+                                    ui.separator();
+                                    if ui.button("🗑 Delete").clicked() {
+                                        self.pending_delete = Some(inst.id);
+                                        ui.close_menu();
+                                    }
+                                });
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label(format!("v{}", inst.version));
+                                if let Some(t) = inst.last_played {
+                                    ui.weak(format!("· played {}", modhistory::format_ago(t, modhistory::now())))
+                                        .on_hover_text(modhistory::format_time(t));
+                                }
+                            });
+                            if let Some(problems) = self.mod_lists.get(&inst.id).map(|m| modinfo::check_dependencies(m, &inst.version)) {
+                                if !problems.is_empty() {
+                                    let text = format!("⚠ {} unmet mod dependenc{}", problems.len(), if problems.len() == 1 { "y" } else { "ies" });
+                                    ui.colored_label(ui.visuals().warn_fg_color, text).on_hover_text(problems.join("\n"));
+                                    for p in problems.iter().take(3) {
+                                        ui.label(egui::RichText::new(format!("• {p}")).small());
+                                    }
+                                }
+                            }
+                            if !inst.notes.trim().is_empty() {
+                                ui.label(egui::RichText::new(Self::notes_preview(&inst.notes)).italics().weak())
+                                    .on_hover_text(&inst.notes);
+                            }
+                            if let Some(p) = &inst.mods_path_override {
+                                ui.label(
+                                    egui::RichText::new(format!("mods: {}", p.display())).small(),
+                                );
+                            }
+                            if let Some(p) = &inst.custom_executable {
+                                ui.label(
+                                    egui::RichText::new(format!("exe: {}", p.display())).small(),
+                                );
+                            }
+                            if let Some(p) = &inst.external_data {
+                                ui.label(
+                                    egui::RichText::new(format!("data: {} (external)", p.display())).small(),
+                                );
+                            }
+                            Self::mods_section(
+                                ui,
+                                inst,
+                                &mut self.mod_lists,
+                                &mut self.mod_locks,
+                                &mut self.pending_mod_remove,
+                                notify,
+                            );
+                            Self::history_section(ui, inst, &mut self.histories, &mut self.pending_restore);
                         });
                         ui.with_layout(
                             egui::Layout::right_to_left(egui::Align::Center),
                             |ui| {
+                                if deleting || copying {
+                                    ui.spinner();
+                                    ui.label(if deleting { "Deleting…" } else { "Copying…" });
+                                    return;
+                                }
                                 if ui.button("🗑").clicked() {
-                                    self.pending_delete = Some(idx);
+                                    self.pending_delete = Some(inst.id);
+                                }
+                                if ui.button("✏").on_hover_text("Edit name, version, mods folder and notes").clicked() {
+                                    edit = Some(inst.id);
+                                }
+                                if ui.button("⧉").on_hover_text("Duplicate with its mods, configs and saves").clicked() {
+                                    duplicate = Some(inst.id);
                                 }
                                 if ui.button("▶").clicked() {
-                                    cmd = InstanceCmd::Play(idx);
+                                    cmd = InstanceCmd::Play(inst.id);
                                 }
                             },
                         );
                     });
-                });
+                }));
+                let card = card.inner.response;
+                if self.selected_row == Some(row) {
+                    ui.painter().rect_stroke(card.rect, 4.0, ui.visuals().selection.stroke);
+                    if key == ListKey::Moved {
+                        card.scroll_to_me(None);
+                    }
+                }
+                if let Some(from) = card.dnd_hover_payload::<usize>() {
+                    if *from != idx && self.same_group(*from, idx) {
+                        ui.painter().rect_stroke(
+                            card.rect,
+                            4.0,
+                            ui.visuals().selection.stroke,
+                        );
+                    }
+                }
+                if let Some(from) = card.dnd_release_payload::<usize>() {
+                    self.pending_move = Some((*from, idx));
+                }
                 ui.add_space(6.0);
             }
-            if let Some(i) = self.pending_delete.take() {
-                self.remove_instance(i);
+            if let Some((id, path)) = self.pending_mod_remove.take() {
+                self.remove_mod(id, &path, notify);
+            }
+            if let Some((id, entry)) = self.pending_restore.take() {
+                self.restore_mod(id, &entry, notify);
+            }
+            if let Some((from, to)) = self.pending_move.take() {
+                self.move_instance(from, to);
+            }
+            if let Some(id) = clear_icon {
+                self.clear_icon(id);
+            }
+            if let Some(id) = star {
+                self.toggle_favorite(id);
+            }
+            if let Some((id, on)) = auto_update {
+                if let Some(inst) = self.instances.iter_mut().find(|i| i.id == id) {
+                    inst.auto_update_mods = on;
+                    self.save_instances();
+                }
+            }
+            if let Some(inst) = validate {
+                self.start_validation(inst, activity);
+            }
+            if let Some(id) = edit {
+                self.open_edit(id);
+            }
+            if let Some(id) = duplicate {
+                self.duplicate_instance(id, activity);
             }
 
             ui.separator();
             if ui.button("New instance…").clicked() {
                 self.new_name.clear();
                 self.new_version.clear();
+                self.new_mods_path.clear();
+                self.new_template = None;
+                self.new_notes.clear();
+                self.editing = None;
+                self.awaiting_version = None;
                 self.show_modal = true;
             }
+            if ui
+                .button("Import existing install…")
+                .on_hover_text("Use a game folder and data folder from outside the launcher, without copying")
+                .clicked()
+            {
+                let data = dirs::config_dir().map(|d| d.join("VintagestoryData")).filter(|d| d.is_dir());
+                self.import_dialog = Some(ImportForm {
+                    data_dir: data.map(|d| d.to_string_lossy().into_owned()).unwrap_or_default(),
+                    ..Default::default()
+                });
+            }
 
             /* modal ---------------------------------------------- */
             if self.show_modal {
-                egui::Window::new("Create instance")
+                let title = if self.editing.is_some() { "Edit instance" } else { "Create instance" };
+                egui::Window::new(title)
+                    .id(egui::Id::new("instance_form"))
                     .collapsible(false)
                     .resizable(false)
                     .show(ctx, |ui| {
-                        ui.label("Name:");
-                        ui.text_edit_singleline(&mut self.new_name);
+                        let waiting = self.awaiting_version.is_some();
+                        ui.add_enabled_ui(!waiting, |ui| self.create_form_ui(ui, versions));
 
-                        ui.label("Game version:");
-                        egui::ComboBox::from_id_source("ver_select")
-                            .selected_text(if self.new_version.is_empty() {
-                                "(choose)".into()
-                            } else {
-                                self.new_version.clone()
-                            })
-                            .show_ui(ui, |ui| {
-                                for ver in Self::installed_versions() {
-                                    ui.selectable_value(&mut self.new_version, ver.clone(), ver);
+                        if let Some(ver) = &self.awaiting_version {
+                            ui.separator();
+                            ui.horizontal(|ui| match versions.fetch_state(ver) {
+                                Some(Fetch::Running(Some(p))) => {
+                                    ui.label(format!("Downloading v{ver}…"));
+                                    ui.add(egui::ProgressBar::new(p).show_percentage());
+                                }
+                                Some(Fetch::Running(None)) => {
+                                    ui.spinner();
+                                    ui.label(format!("Downloading v{ver}…"));
+                                }
+                                Some(Fetch::Queued) => {
+                                    ui.spinner();
+                                    ui.label(format!("v{ver} is queued behind another download"));
+                                }
+                                None => {
+                                    ui.spinner();
+                                    ui.label(format!("Installing v{ver}…"));
                                 }
                             });
+                            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+                        }
 
                         ui.horizontal(|ui| {
-                            if ui.button("Create").clicked() {
-                                if !self.new_name.is_empty() && !self.new_version.is_empty() {
-                                    self.create_instance();
-                                    self.show_modal = false;
+                            let installed = self.new_version.is_empty() || Self::version_installed(&self.new_version);
+                            let label = match (self.editing.is_some(), installed) {
+                                (false, true) => "Create",
+                                (false, false) => "Download & create",
+                                (true, true) => "Save",
+                                (true, false) => "Download & save",
+                            };
+                            let name_ok = self.check_name(self.new_name.trim(), self.editing);
+                            let ready = !waiting && name_ok.is_ok() && !self.new_version.is_empty();
+                            let submit = ui.add_enabled(ready, egui::Button::new(label));
+                            let submit = match &name_ok {
+                                Err(e) if !self.new_name.trim().is_empty() => submit.on_disabled_hover_text(e),
+                                _ => submit,
+                            };
+                            if submit.clicked() {
+                                if Self::version_installed(&self.new_version) {
+                                    self.submit_form(notify);
+                                } else {
+                                    self.awaiting_version = Some(self.new_version.clone());
+                                    cmd = InstanceCmd::Download(self.new_version.clone());
                                 }
                             }
-                            if ui.button("Cancel").clicked() { self.show_modal = false; }
+                            let cancel = ui.button("Cancel");
+                            let cancel = if waiting {
+                                cancel.on_hover_text("The download keeps going; stop it from Tasks")
+                            } else {
+                                cancel
+                            };
+                            if cancel.clicked() {
+                                self.awaiting_version = None;
+                                self.show_modal = false;
+                            }
                         });
                     });
             }
+            self.delete_dialog_ui(ctx, activity, notify);
+            self.icon_dialog_ui(ctx, notify);
+            self.notes_dialog_ui(ctx);
+            self.exe_dialog_ui(ctx, notify);
+            self.import_dialog_ui(ctx, notify);
+            if let Some(play) = self.validation_ui(ctx, activity) {
+                cmd = play;
+            }
         });
 
         cmd
     }
 
-    fn create_instance(&mut self) {
-        let root = data_local_dir()
-            .unwrap_or_else(|| PathBuf::from("~/.local/share"))
-            .join("vs_launcher/instances")
-            .join(&self.new_name);
-        let _ = fs::create_dir_all(root.join("mods"));
+    /// Name / version / mods / notes / template fields of the create dialog.
+    /// The version list offers known-but-missing versions after the installed ones.
+    fn create_form_ui(&mut self, ui: &mut egui::Ui, versions: &VersionPage) {
+        ui.label("Name:");
+        ui.text_edit_singleline(&mut self.new_name);
+
+        ui.label("Game version:");
+        egui::ComboBox::from_id_source("ver_select")
+            .selected_text(if self.new_version.is_empty() {
+                "(choose)".into()
+            } else if Self::version_installed(&self.new_version) {
+                self.new_version.clone()
+            } else {
+                format!("{} (download)", self.new_version)
+            })
+            .show_ui(ui, |ui| {
+                for ver in Self::installed_versions() {
+                    ui.selectable_value(&mut self.new_version, ver.clone(), ver);
+                }
+                let missing = versions.not_installed();
+                if !missing.is_empty() {
+                    ui.separator();
+                    ui.weak("Not installed – downloaded first:");
+                }
+                for ver in missing {
+                    ui.selectable_value(&mut self.new_version, ver.clone(), format!("⬇ {ver}"));
+                }
+                if versions.list_loading() {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.weak("Fetching list…");
+                    });
+                }
+            });
+
+        ui.label("Mods folder (optional):");
+        ui.add(
+            egui::TextEdit::singleline(&mut self.new_mods_path)
+                .hint_text("default: <instance>/mods"),
+        );
+
+        ui.label("Notes (optional):");
+        ui.add(
+            egui::TextEdit::multiline(&mut self.new_notes)
+                .desired_rows(2)
+                .hint_text("what is this instance for?"),
+        );
+
+        if self.editing.is_some() {
+            return;
+        }
+        ui.label("Copy settings from (optional):");
+        egui::ComboBox::from_id_source("template_select")
+            .selected_text(self.new_template.as_deref().unwrap_or("(none)"))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut self.new_template, None, "(none)");
+                for inst in &self.instances {
+                    ui.selectable_value(&mut self.new_template, Some(inst.name.clone()), &inst.name);
+                }
+            });
+        let mut folder = self
+            .new_template
+            .as_ref()
+            .filter(|t| !self.instances.iter().any(|i| &i.name == *t))
+            .cloned()
+            .unwrap_or_default();
+        if ui
+            .add(egui::TextEdit::singleline(&mut folder).hint_text("…or a data folder"))
+            .changed()
+        {
+            self.new_template = (!folder.trim().is_empty()).then(|| folder.trim().to_string());
+        }
+    }
+
+    /// Creates the instance the dialog was waiting on once its version has
+    /// landed; a failed or cancelled download leaves the dialog open to retry.
+    fn poll_awaited_version(&mut self, versions: &VersionPage, notify: &Notifier) {
+        let Some(ver) = self.awaiting_version.clone() else { return };
+        if versions.fetch_state(&ver).is_some() {
+            return;
+        }
+        self.awaiting_version = None;
+        if Self::version_installed(&ver) {
+            self.submit_form(notify);
+        } else {
+            let what = if self.editing.is_some() { "saved" } else { "created" };
+            notify.error(format!("v{ver} was not installed; {} was not {what}", self.new_name));
+        }
+    }
+
+    /// Creates the instance, or saves the edited one, from the dialog's
+    /// fields; the dialog closes unless that fails.
+    fn submit_form(&mut self, notify: &Notifier) {
+        let res = match self.editing {
+            Some(id) => self.apply_edit(id).inspect(|_| self.save_instances()),
+            None => self.create_instance(notify),
+        };
+        match res {
+            Ok(msg) => {
+                notify.success(msg);
+                self.show_modal = false;
+                self.editing = None;
+            }
+            Err(e) => notify.error(e),
+        }
+    }
+
+    /// Opens the create dialog filled in with instance `id` for editing.
+    fn open_edit(&mut self, id: u64) {
+        let Some(inst) = self.get(id).cloned() else { return };
+        self.new_name = inst.name.clone();
+        self.new_version = inst.version.clone();
+        self.new_mods_path = inst
+            .mods_path_override
+            .as_ref()
+            .map(|p| p.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        self.new_notes = inst.notes.clone();
+        self.new_template = None;
+        self.awaiting_version = None;
+        self.editing = Some(id);
+        self.show_modal = true;
+    }
 
-        self.instances.push(Instance {
-            name: self.new_name.clone(),
+    /// Applies the dialog's fields to instance `id`. A new name moves the
+    /// managed data folder (and the launcher's icon folder) along with it;
+    /// adopted data folders stay where they are. Does not save.
+    fn apply_edit(&mut self, id: u64) -> Result<String, String> {
+        let name = self.new_name.trim().to_string();
+        self.check_name(&name, Some(id))?;
+        let Some(idx) = self.instances.iter().position(|i| i.id == id) else {
+            return Err("No such instance".into());
+        };
+        let old = self.instances[idx].clone();
+        if name != old.name {
+            let mut renamed = self.instances[idx].clone();
+            renamed.name = name.clone();
+            // the icon folder is the data folder unless a custom root is set
+            let mut moves = vec![(paths::instance_dir(None, &old.name), paths::instance_dir(None, &name))];
+            if old.external_data.is_none() && old.data_root.is_some() {
+                moves.push((old.data_dir(), renamed.data_dir()));
+            }
+            let mut done: Vec<&(PathBuf, PathBuf)> = Vec::new();
+            for pair @ (from, to) in &moves {
+                if !from.exists() {
+                    continue;
+                }
+                let res = if to.exists() {
+                    Err(format!("{} already exists", to.display()))
+                } else {
+                    fs::rename(from, to).map_err(|e| format!("Could not rename {}: {e}", from.display()))
+                };
+                if let Err(e) = res {
+                    // put back what was already moved, so the instance keeps its folders
+                    for (from, to) in done.into_iter().rev() {
+                        let _ = fs::rename(to, from);
+                    }
+                    return Err(e);
+                }
+                done.push(pair);
+            }
+            let old_icons = paths::instance_dir(None, &old.name);
+            if let Some(icon) = &old.icon {
+                if let Ok(rest) = icon.strip_prefix(&old_icons) {
+                    self.icons.forget(icon);
+                    renamed.icon = Some(paths::instance_dir(None, &name).join(rest));
+                }
+            }
+            self.instances[idx] = renamed;
+        }
+        let override_path = self.new_mods_path.trim();
+        let inst = &mut self.instances[idx];
+        inst.version = self.new_version.clone();
+        inst.mods_path_override = (!override_path.is_empty()).then(|| PathBuf::from(override_path));
+        inst.notes = self.new_notes.trim().to_string();
+        self.mod_lists.remove(&id);
+        self.mod_locks.remove(&id);
+        self.histories.remove(&id);
+        Ok(format!("Saved {name}"))
+    }
+
+    /// Whether `name` (already trimmed) can name an instance: folders are
+    /// keyed by it, so it must be a single path component and no other
+    /// instance than `except` may have it.
+    fn check_name(&self, name: &str, except: Option<u64>) -> Result<(), String> {
+        if name.is_empty() || name.contains(['/', '\\']) || name == "." || name == ".." {
+            return Err("Enter a name without slashes".into());
+        }
+        if self.instances.iter().any(|i| Some(i.id) != except && i.name == name) {
+            return Err(format!("An instance named {name} already exists"));
+        }
+        Ok(())
+    }
+
+    fn version_installed(ver: &str) -> bool {
+        paths::install_dir(ver).is_dir()
+    }
+
+    fn start_validation(&mut self, inst: Instance, activity: &Activity) {
+        self.validation = None;
+        let id = inst.id;
+        let task = activity.spawn(format!("Validating {}", inst.name), move |task| task.post(preflight::validate(&inst)));
+        self.validation_task = Some((id, task));
+    }
+
+    /// Pass/fail checklist window; returns a Play request if the user starts it from there.
+    fn validation_ui(&mut self, ctx: &egui::Context, activity: &Activity) -> Option<InstanceCmd> {
+        if let Some((id, task)) = self.validation_task {
+            match activity.try_take::<Vec<Check>>(task) {
+                Ok(checks) => {
+                    self.validation = Some((id, checks));
+                    self.validation_task = None;
+                }
+                Err(TryRecvError::Disconnected) => self.validation_task = None,
+                Err(TryRecvError::Empty) => {}
+            }
+        }
+        let id = self.validation.as_ref().map(|(id, _)| *id).or(self.validation_task.map(|(id, _)| id))?;
+        let name = self.get(id).map(|i| i.name.clone()).unwrap_or_default();
+        let mut open = true;
+        let mut play = None;
+        egui::Window::new(format!("Validate {name}"))
+            .collapsible(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                let Some((_, checks)) = &self.validation else {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Checking…");
+                    });
+                    return;
+                };
+                for c in checks {
+                    ui.horizontal(|ui| {
+                        match &c.result {
+                            Ok(()) => ui.colored_label(egui::Color32::from_rgb(90, 170, 90), "✔"),
+                            Err(_) => ui.colored_label(ui.visuals().error_fg_color, "✖"),
+                        };
+                        ui.label(c.label);
+                    });
+                    if let Err(e) = &c.result {
+                        ui.indent(("check", c.label), |ui| {
+                            ui.label(egui::RichText::new(e).small());
+                        });
+                    }
+                }
+                ui.separator();
+                let ok = checks.iter().all(|c| c.result.is_ok());
+                if ui.button(if ok { "▶ Play" } else { "▶ Play anyway" }).clicked() {
+                    play = Some(InstanceCmd::Play(id));
+                }
+            });
+        if !open || play.is_some() {
+            self.validation = None;
+            if let Some((_, task)) = self.validation_task.take() {
+                activity.forget(task);
+            }
+        }
+        play
+    }
+
+    /// First line of the notes, cut to fit on a card.
+    fn notes_preview(notes: &str) -> String {
+        const MAX: usize = 60;
+        let line = notes.trim().lines().next().unwrap_or_default();
+        let more = line.chars().count() > MAX || notes.trim().lines().nth(1).is_some();
+        let mut out: String = line.chars().take(MAX).collect();
+        if more {
+            out.push('…');
+        }
+        out
+    }
+
+    /// Asks before `pending_delete` goes; managed instances lose their
+    /// whole folder, adopted ones are only forgotten.
+    fn delete_dialog_ui(&mut self, ctx: &egui::Context, activity: &Activity, notify: &Notifier) {
+        let Some(inst) = self.pending_delete.and_then(|id| self.get(id)) else {
+            self.pending_delete = None;
+            return;
+        };
+        let id = inst.id;
+        let (mut go, mut cancel) = (false, false);
+        egui::Window::new(format!("Delete {}?", inst.name))
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                match (&inst.external_data, self.sharing_data(inst)) {
+                    (Some(p), _) => {
+                        ui.label("The launcher will forget this instance.");
+                        ui.label(format!("Its data folder {} is kept.", p.display()));
+                    }
+                    (None, Some(other)) => {
+                        ui.label("The launcher will forget this instance.");
+                        ui.label(format!("Its data folder is also {other}'s, so it is kept."));
+                    }
+                    (None, None) => {
+                        ui.colored_label(
+                            ui.visuals().warn_fg_color,
+                            "⚠ All of its mods, configs and saves will be deleted:",
+                        );
+                        ui.label(inst.data_dir().display().to_string());
+                    }
+                }
+                ui.horizontal(|ui| {
+                    go = ui.button("Delete").clicked();
+                    cancel = ui.button("Cancel").clicked();
+                });
+            });
+        if go {
+            self.remove_instance(id, activity, notify);
+        }
+        if go || cancel {
+            self.pending_delete = None;
+        }
+    }
+
+    fn notes_dialog_ui(&mut self, ctx: &egui::Context) {
+        let Some((id, text)) = &mut self.notes_dialog else { return };
+        let id = *id;
+        let (mut save, mut close) = (false, false);
+        egui::Window::new("Instance notes")
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.add(egui::TextEdit::multiline(text).desired_rows(6));
+                ui.horizontal(|ui| {
+                    save = ui.button("Save").clicked();
+                    close = ui.button("Cancel").clicked();
+                });
+            });
+        if save {
+            let notes = text.trim().to_string();
+            if let Some(inst) = self.instances.iter_mut().find(|i| i.id == id) {
+                inst.notes = notes;
+                self.save_instances();
+            }
+            close = true;
+        }
+        if close {
+            self.notes_dialog = None;
+        }
+    }
+
+    fn import_dialog_ui(&mut self, ctx: &egui::Context, notify: &Notifier) {
+        let Some(form) = &mut self.import_dialog else { return };
+        let (mut import, mut close) = (false, false);
+        egui::Window::new("Import existing install")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label("Name:");
+                ui.text_edit_singleline(&mut form.name);
+                ui.label("Game version:");
+                ui.add(egui::TextEdit::singleline(&mut form.version).hint_text("e.g. 1.19.8"));
+                ui.label("Game folder:");
+                ui.add(egui::TextEdit::singleline(&mut form.game_dir).hint_text("folder containing Vintagestory"));
+                ui.label("Data folder:");
+                ui.add(egui::TextEdit::singleline(&mut form.data_dir).hint_text("~/.config/VintagestoryData"));
+                ui.label(egui::RichText::new("Nothing is copied; deleting the instance later keeps both folders.").small().weak());
+                ui.horizontal(|ui| {
+                    import = ui.button("Import").clicked();
+                    close = ui.button("Cancel").clicked();
+                });
+            });
+        if import {
+            match self.import_instance() {
+                Ok(name) => {
+                    notify.success(format!("Imported {name}"));
+                    close = true;
+                }
+                Err(e) => notify.error(e),
+            }
+        }
+        if close {
+            self.import_dialog = None;
+        }
+    }
+
+    /// Registers the install described by the import form; nothing on disk moves.
+    fn import_instance(&mut self) -> Result<String, String> {
+        let Some(form) = &self.import_dialog else { return Err("Nothing to import".into()) };
+        let name = form.name.trim().to_string();
+        let version = form.version.trim().trim_start_matches('v').to_string();
+        if version.is_empty() {
+            return Err("Name and game version are required".into());
+        }
+        self.check_name(&name, None)?;
+        let game_dir = PathBuf::from(form.game_dir.trim());
+        let exe = launch::find_executable(&game_dir)
+            .ok_or_else(|| format!("No game executable in {}", game_dir.display()))?;
+        let data_dir = PathBuf::from(form.data_dir.trim());
+        if !data_dir.is_dir() {
+            return Err(format!("{} is not a folder", data_dir.display()));
+        }
+        // the game's own mods folder, so the mod list shows what it loads
+        let mods = data_dir.join("Mods");
+        let inst = Instance {
+            id: self.next_id(),
+            name: name.clone(),
+            version,
+            mods_path_override: mods.is_dir().then_some(mods),
+            icon: None,
+            notes: String::new(),
+            favorite: false,
+            custom_executable: Some(exe),
+            external_data: Some(data_dir),
+            data_root: None,
+            auto_update_mods: false,
+            last_played: None,
+        };
+        self.instances.push(inst);
+        self.save_instances();
+        Ok(name)
+    }
+
+    fn exe_dialog_ui(&mut self, ctx: &egui::Context, notify: &Notifier) {
+        let Some((id, path)) = &mut self.exe_dialog else { return };
+        let id = *id;
+        let (mut save, mut close) = (false, false);
+        egui::Window::new("Custom executable")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label("Run this binary instead of the installed version's:");
+                ui.add(egui::TextEdit::singleline(path).hint_text("empty = use the managed install"));
+                ui.label(egui::RichText::new("--dataPath and --addModPath are still passed").small().weak());
+                ui.horizontal(|ui| {
+                    save = ui.button("Save").clicked();
+                    close = ui.button("Cancel").clicked();
+                });
+            });
+        if save {
+            let path = path.trim();
+            let exe = (!path.is_empty()).then(|| PathBuf::from(path));
+            if exe.as_ref().is_some_and(|p| !p.is_file()) {
+                notify.error(format!("{path} is not a file"));
+            } else {
+                if let Some(inst) = self.instances.iter_mut().find(|i| i.id == id) {
+                    inst.custom_executable = exe;
+                    self.save_instances();
+                }
+                close = true;
+            }
+        }
+        if close {
+            self.exe_dialog = None;
+        }
+    }
+
+    fn icon_dialog_ui(&mut self, ctx: &egui::Context, notify: &Notifier) {
+        let Some((id, src)) = &mut self.icon_dialog else { return };
+        let id = *id;
+        let (mut apply, mut close) = (false, false);
+        egui::Window::new("Set instance icon")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label("Image file (PNG or JPEG):");
+                ui.add(egui::TextEdit::singleline(src).hint_text("/path/to/picture.png"));
+                ui.horizontal(|ui| {
+                    apply = ui.add_enabled(!src.trim().is_empty(), egui::Button::new("Set")).clicked();
+                    close = ui.button("Cancel").clicked();
+                });
+            });
+        if apply {
+            let src = PathBuf::from(src.trim());
+            match self.set_icon(id, &src) {
+                Ok(()) => {
+                    notify.success("Icon updated");
+                    close = true;
+                }
+                Err(e) => notify.error(e),
+            }
+        }
+        if close {
+            self.icon_dialog = None;
+        }
+    }
+
+    /// Opens a folder the game may not have created yet.
+    fn open_dir(dir: &Path) {
+        let _ = fs::create_dir_all(dir);
+        let _ = open::that(dir);
+    }
+
+    /// Collapsible list of the instance's installed mods.
+    fn mods_section(
+        ui: &mut egui::Ui,
+        inst: &Instance,
+        mod_lists: &mut HashMap<u64, Vec<InstalledMod>>,
+        mod_locks: &mut HashMap<u64, ModLock>,
+        pending_remove: &mut Option<(u64, PathBuf)>,
+        notify: &Notifier,
+    ) {
+        let header = match mod_lists.get(&inst.id) {
+            Some(list) => format!("Mods ({})", list.len()),
+            None => "Mods".into(),
+        };
+        egui::CollapsingHeader::new(header)
+            .id_source(("inst_mods", inst.id))
+            .show(ui, |ui| {
+                let list = mod_lists
+                    .entry(inst.id)
+                    .or_insert_with(|| modinfo::list_mods(&inst.mods_dir()));
+                let lock = mod_locks.entry(inst.id).or_insert_with(|| ModLock::load(inst));
+                let mut lock_changed = false;
+                ui.horizontal(|ui| {
+                    if ui.small_button("↻ Rescan").clicked() {
+                        *list = modinfo::list_mods(&inst.mods_dir());
+                        *lock = ModLock::load(inst);
+                    }
+                    if !list.is_empty()
+                        && ui.small_button("🔒 Lock all").on_hover_text("Pin every mod at its installed version").clicked()
+                    {
+                        lock.lock_all(list);
+                        lock_changed = true;
+                    }
+                    if !lock.mods.is_empty() && ui.small_button("🔓 Unlock all").clicked() {
+                        lock.mods.clear();
+                        lock_changed = true;
+                    }
+                });
+                if list.is_empty() {
+                    ui.label(egui::RichText::new("No mods installed").weak());
+                }
+                egui::Grid::new(("inst_mods_grid", inst.id)).num_columns(4).striped(true).show(ui, |ui| {
+                    for m in list.iter() {
+                        match &m.info {
+                            Ok(info) => {
+                                let kind = m.kind().map(|k| k.label()).unwrap_or("?");
+                                ui.label(&info.name).on_hover_text(format!("{} ({kind})", m.file_name()));
+                                ui.label(&info.version);
+                                ui.label(egui::RichText::new(&info.modid).small().weak());
+                            }
+                            Err(e) => {
+                                ui.label(m.file_name());
+                                ui.colored_label(ui.visuals().warn_fg_color, "unknown")
+                                    .on_hover_text(format!("No readable modinfo.json: {e}"));
+                                ui.label("");
+                            }
+                        }
+                        ui.horizontal(|ui| {
+                            if let Ok(info) = &m.info {
+                                match lock.pinned(&info.modid).map(str::to_owned) {
+                                    Some(pin) => {
+                                        if pin != info.version {
+                                            ui.colored_label(ui.visuals().warn_fg_color, format!("pinned {pin}"));
+                                        }
+                                        if ui.small_button("🔒").on_hover_text(format!("Pinned to {pin}; click to unlock")).clicked() {
+                                            lock.unlock(&info.modid);
+                                            lock_changed = true;
+                                        }
+                                    }
+                                    None => {
+                                        if ui.small_button("🔓").on_hover_text("Pin this version").clicked() {
+                                            lock.lock(&info.modid, &info.version);
+                                            lock_changed = true;
+                                        }
+                                    }
+                                }
+                            }
+                            if ui.small_button("🗑").on_hover_text("Remove mod").clicked() {
+                                *pending_remove = Some((inst.id, m.path.clone()));
+                            }
+                        });
+                        ui.end_row();
+                    }
+                });
+                if lock_changed {
+                    if let Err(e) = lock.save(inst) {
+                        notify.error(format!("Could not save modlock.json: {e}"));
+                    }
+                }
+            });
+    }
+
+    fn remove_mod(&mut self, inst_id: u64, path: &Path, notify: &Notifier) {
+        let inst = self.get(inst_id);
+        let info = modinfo::read_modinfo(path).ok();
+        // a copy of what is removed lets the history put it back
+        let kept = inst.and_then(|i| ModHistory::keep(i, path).ok());
+        let res = if path.is_dir() { fs::remove_dir_all(path) } else { fs::remove_file(path) };
+        match res {
+            Ok(()) => {
+                notify.success(format!("Removed {}", path.display()));
+                if let (Some(inst), Some(info)) = (inst, info) {
+                    let entry = Entry { kept, ..Entry::new(Change::Removed, &info) };
+                    if let Err(e) = ModHistory::record(inst, entry) {
+                        notify.error(format!("Could not save instance_history.json: {e}"));
+                    }
+                }
+            }
+            Err(e) => notify.error(format!("Remove error: {e}")),
+        }
+        if let Some(list) = self.mod_lists.get_mut(&inst_id) {
+            list.retain(|m| m.path != path);
+        }
+        self.histories.remove(&inst_id);
+    }
+
+    fn restore_mod(&mut self, inst_id: u64, entry: &Entry, notify: &Notifier) {
+        let Some(inst) = self.get(inst_id) else { return };
+        notify.result(
+            ModHistory::restore(inst, entry)
+                .map(|()| format!("Restored {} {}", entry.name, entry.kept_version())),
+        );
+        self.mod_lists.remove(&inst_id);
+        self.histories.remove(&inst_id);
+    }
+
+    /// Collapsible log of mod installs, updates and removals, newest first.
+    fn history_section(
+        ui: &mut egui::Ui,
+        inst: &Instance,
+        histories: &mut HashMap<u64, ModHistory>,
+        pending_restore: &mut Option<(u64, Entry)>,
+    ) {
+        egui::CollapsingHeader::new("History")
+            .id_source(("inst_history", inst.id))
+            .show(ui, |ui| {
+                let history = histories.entry(inst.id).or_insert_with(|| ModHistory::load(inst));
+                if ui.small_button("↻ Reload").clicked() {
+                    *history = ModHistory::load(inst);
+                }
+                if history.entries.is_empty() {
+                    ui.label(egui::RichText::new("No mod changes recorded yet").weak());
+                }
+                egui::ScrollArea::vertical()
+                    .id_source(("inst_history_scroll", inst.id))
+                    .max_height(200.0)
+                    .show(ui, |ui| {
+                        for entry in history.entries.iter().rev() {
+                            ui.horizontal(|ui| {
+                                ui.label(egui::RichText::new(modhistory::format_time(entry.at)).small().weak());
+                                let what = match &entry.from {
+                                    Some(from) => format!("{} {} {from} → {}", entry.change.label(), entry.name, entry.version),
+                                    None => format!("{} {} {}", entry.change.label(), entry.name, entry.version),
+                                };
+                                ui.label(what).on_hover_text(format!("modid: {}", entry.modid));
+                                if entry.restorable().is_some()
+                                    && ui
+                                        .small_button("↶")
+                                        .on_hover_text(format!("Restore {}", entry.kept_version()))
+                                        .clicked()
+                                {
+                                    *pending_restore = Some((inst.id, entry.clone()));
+                                }
+                            });
+                        }
+                    });
+            });
+    }
+
+    fn create_instance(&mut self, notify: &Notifier) -> Result<String, String> {
+        let name = self.new_name.trim().to_string();
+        self.check_name(&name, None)?;
+        let id = self.next_id();
+        let override_path = self.new_mods_path.trim();
+        let inst = Instance {
+            id,
+            name: name.clone(),
             version: self.new_version.clone(),
-        });
+            mods_path_override: (!override_path.is_empty()).then(|| PathBuf::from(override_path)),
+            icon: None,
+            notes: self.new_notes.trim().to_string(),
+            favorite: false,
+            custom_executable: None,
+            external_data: None,
+            // settings only steer new instances; existing ones stay put
+            data_root: Config::load().instances_root(),
+            auto_update_mods: false,
+            last_played: None,
+        };
+        let _ = fs::create_dir_all(inst.data_dir());
+        let _ = fs::create_dir_all(inst.mods_dir());
+
+        if let Some(template) = self.new_template.take() {
+            // an instance name, else a folder path
+            let src = match self.instances.iter().find(|i| i.name == template) {
+                Some(t) => t.data_dir(),
+                None => PathBuf::from(&template),
+            };
+            notify.result(match Self::copy_template(&src, &inst.data_dir()) {
+                Ok(0) => Err(format!("Nothing to copy from {}", src.display())),
+                Ok(n) => Ok(format!("Copied {n} config item(s) from {template}")),
+                Err(e) => Err(format!("Template copy error: {e}")),
+            });
+        }
+
+        self.instances.push(inst);
         self.save_instances();
+        Ok(format!("Created {name}"))
+    }
+
+    /// Copies the `TEMPLATE_ITEMS` present in `src` into `dest`; returns how many.
+    fn copy_template(src: &Path, dest: &Path) -> io::Result<usize> {
+        let mut n = 0;
+        for item in TEMPLATE_ITEMS {
+            let from = src.join(item);
+            if from.exists() {
+                disk::copy_recursive(&from, &dest.join(item))?;
+                n += 1;
+            }
+        }
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use eframe::egui::Key;
+
+    use super::*;
+    use crate::{pages::harness, testutil};
+
+    fn inst(json: &str) -> Instance {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn page() -> InstancesPage {
+        InstancesPage::with_instances(vec![
+            inst(r#"{"id": 1, "name": "Alpha", "version": "1.19.8"}"#),
+            inst(r#"{"id": 2, "name": "Beta", "version": "1.20.0", "favorite": true}"#),
+            inst(r#"{"id": 3, "name": "Gamma", "version": "1.20.0"}"#),
+        ])
+    }
+
+    /// One frame of the page with `keys` pressed; returns the command it emitted.
+    fn frame(page: &mut InstancesPage, ctx: &egui::Context, keys: &[Key]) -> InstanceCmd {
+        let events = keys.iter().map(|&k| harness::press(k)).collect();
+        harness::frame(ctx, events, |ctx| page.ui(ctx, &Activity::default(), &Notifier::default(), &VersionPage::default()))
+    }
+
+    #[test]
+    fn dependency_checks_read_unscanned_instances_and_see_new_mods() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mods = tmp.path().join("mods");
+        let folder_mod = |modid: &str, info: &str| {
+            fs::create_dir_all(mods.join(modid)).unwrap();
+            fs::write(mods.join(modid).join("modinfo.json"), info).unwrap();
+        };
+        folder_mod("pets", r#"{"modid": "pets", "name": "Pets", "dependencies": {"petcore": "*"}}"#);
+        let mut page = InstancesPage::with_instances(vec![testutil::instance(tmp.path())]);
+        // never scanned: read on the spot rather than waved through
+        assert_eq!(page.dependency_problems(1), ["Pets needs petcore, which is not installed"]);
+
+        folder_mod("petcore", r#"{"modid": "petcore", "name": "Pet Core"}"#);
+        assert_eq!(page.dependency_problems(1).len(), 1, "cached until told");
+        page.mods_changed(1);
+        assert!(page.dependency_problems(1).is_empty());
+    }
+
+    #[test]
+    fn favorites_come_first_then_list_order() {
+        let page = page();
+        assert_eq!(page.ordered(""), vec![1, 0, 2]);
+    }
+
+    #[test]
+    fn recent_sort_puts_the_last_played_first() {
+        let mut page = page();
+        page.instances[0].last_played = Some(100);
+        page.instances[2].last_played = Some(200);
+        page.sort_recent = true;
+        // favorite Beta (never played) still leads
+        assert_eq!(page.ordered(""), vec![1, 2, 0]);
+    }
+
+    #[test]
+    fn drops_stay_within_the_favorites_or_the_rest() {
+        let tmp = tempfile::tempdir().unwrap();
+        paths::use_test_base_dir(tmp.path());
+        let mut page = page();
+        page.instances[2].favorite = true;
+        let names = |page: &InstancesPage| -> Vec<String> {
+            page.ordered("").into_iter().map(|i| page.instances[i].name.clone()).collect()
+        };
+        assert_eq!(names(&page), ["Beta", "Gamma", "Alpha"]);
+        // Gamma dropped on Beta takes its place
+        page.move_instance(2, 1);
+        assert_eq!(names(&page), ["Gamma", "Beta", "Alpha"]);
+        // Alpha can't be dropped among the favorites
+        let before: Vec<u64> = page.instances.iter().map(|i| i.id).collect();
+        page.move_instance(0, 1);
+        assert_eq!(page.instances.iter().map(|i| i.id).collect::<Vec<_>>(), before);
+    }
+
+    #[test]
+    fn enter_on_highlighted_card_plays_it() {
+        let tmp = tempfile::tempdir().unwrap();
+        paths::use_test_base_dir(tmp.path());
+        let (mut page, ctx) = (page(), harness::context());
+        assert!(matches!(frame(&mut page, &ctx, &[]), InstanceCmd::None));
+        frame(&mut page, &ctx, &[Key::ArrowDown]); // favorite Beta is row 0
+        frame(&mut page, &ctx, &[Key::ArrowDown]);
+        assert!(matches!(frame(&mut page, &ctx, &[Key::Enter]), InstanceCmd::Play(1)));
+    }
+
+    #[test]
+    fn enter_plays_the_search_match() {
+        let tmp = tempfile::tempdir().unwrap();
+        paths::use_test_base_dir(tmp.path());
+        let (mut page, ctx) = (page(), harness::context());
+        page.search = "gam".into();
+        frame(&mut page, &ctx, &[Key::ArrowDown]);
+        assert!(matches!(frame(&mut page, &ctx, &[Key::Enter]), InstanceCmd::Play(3)));
+    }
+
+    #[test]
+    fn editing_renames_the_data_folder_and_rejects_taken_names() {
+        let tmp = tempfile::tempdir().unwrap();
+        paths::use_test_base_dir(&tmp.path().join("base"));
+        let original: Instance = serde_json::from_value(serde_json::json!({
+            "id": 1, "name": "Rename Test", "version": "1.19.8", "data_root": tmp.path(),
+        }))
+        .unwrap();
+        fs::create_dir_all(original.mods_dir()).unwrap();
+        let icons = paths::instance_dir(None, "Rename Test");
+        fs::create_dir_all(&icons).unwrap();
+        let mut page = InstancesPage::with_instances(vec![original, inst(r#"{"id": 2, "name": "Beta", "version": "1.20.0"}"#)]);
+        page.open_edit(1);
+
+        page.new_name = "Beta".into();
+        assert!(page.apply_edit(1).unwrap_err().contains("already exists"));
+
+        page.new_name = "Renamed Test".into();
+        page.new_version = "1.20.0".into();
+        page.apply_edit(1).unwrap();
+        let renamed = page.get(1).unwrap();
+        assert_eq!((renamed.name.as_str(), renamed.version.as_str()), ("Renamed Test", "1.20.0"));
+        assert!(tmp.path().join("Renamed Test/mods").is_dir());
+        assert!(!tmp.path().join("Rename Test").exists());
+        assert!(paths::instance_dir(None, "Renamed Test").is_dir());
+        assert!(!icons.exists());
+    }
+
+    #[test]
+    fn deleting_under_a_custom_root_removes_the_icon_folder_too() {
+        let tmp = tempfile::tempdir().unwrap();
+        paths::use_test_base_dir(&tmp.path().join("base"));
+        let activity = Activity::default();
+        let inst: Instance = serde_json::from_value(serde_json::json!({
+            "id": 1, "name": "Custom", "version": "1.20.0", "data_root": tmp.path().join("data"),
+        }))
+        .unwrap();
+        let (data, icons) = (inst.data_dir(), paths::instance_dir(None, "Custom"));
+        fs::create_dir_all(&data).unwrap();
+        fs::create_dir_all(&icons).unwrap();
+        let mut page = InstancesPage::with_instances(vec![inst]);
+
+        page.remove_instance(1, &activity, &Notifier::default());
+        while !page.deleting.is_empty() {
+            page.poll_deletes(&activity, &Notifier::default());
+        }
+        assert!(page.get(1).is_none());
+        assert!(!data.exists());
+        assert!(!icons.exists());
+    }
+
+    #[test]
+    fn a_failed_rename_moves_the_first_folder_back() {
+        let tmp = tempfile::tempdir().unwrap();
+        paths::use_test_base_dir(&tmp.path().join("base"));
+        let original: Instance = serde_json::from_value(serde_json::json!({
+            "id": 1, "name": "Old", "version": "1.20.0", "data_root": tmp.path().join("data"),
+        }))
+        .unwrap();
+        // icon folder moves first, then the data folder runs into a stray one
+        fs::create_dir_all(paths::instance_dir(None, "Old")).unwrap();
+        fs::create_dir_all(original.data_dir()).unwrap();
+        fs::create_dir_all(tmp.path().join("data/New")).unwrap();
+        let mut page = InstancesPage::with_instances(vec![original]);
+        page.open_edit(1);
+        page.new_name = "New".into();
+
+        assert!(page.apply_edit(1).unwrap_err().contains("already exists"));
+        assert!(paths::instance_dir(None, "Old").is_dir());
+        assert!(!paths::instance_dir(None, "New").exists());
+        assert_eq!(page.get(1).unwrap().name, "Old");
+    }
+
+    #[test]
+    fn copies_get_a_free_name() {
+        let tmp = tempfile::tempdir().unwrap();
+        paths::use_test_base_dir(tmp.path());
+        let root = tmp.path().join("elsewhere");
+        let mut page = page();
+        assert_eq!(page.copy_name("Alpha", None), "Alpha (copy)");
+        page.instances.push(inst(r#"{"id": 9, "name": "Alpha (copy)", "version": "1.20.0"}"#));
+        assert_eq!(page.copy_name("Alpha", None), "Alpha (copy 2)");
+        // leftover folders nobody lists still count as taken
+        fs::create_dir_all(paths::instance_dir(None, "Alpha (copy 2)")).unwrap();
+        fs::create_dir_all(paths::instance_dir(Some(&root), "Alpha (copy 3)")).unwrap();
+        assert_eq!(page.copy_name("Alpha", Some(&root)), "Alpha (copy 4)");
+    }
+
+    #[test]
+    fn ids_of_deleted_instances_are_not_handed_out_again() {
+        let tmp = tempfile::tempdir().unwrap();
+        paths::use_test_base_dir(tmp.path());
+        let mut page = page();
+        page.instances.retain(|i| i.id != 3);
+        page.save_instances();
+
+        let mut reloaded = InstancesPage::default();
+        assert_eq!(reloaded.instances.len(), 2);
+        assert_eq!(reloaded.next_id(), 4);
+        assert_eq!(reloaded.next_id(), 5);
+    }
+
+    #[test]
+    fn bare_lists_from_before_the_counter_still_load() {
+        let tmp = tempfile::tempdir().unwrap();
+        paths::use_test_base_dir(tmp.path());
+        fs::write(paths::instances_file(), r#"[{"id": 2, "name": "Old", "version": "1.19.8"}]"#).unwrap();
+        let mut page = InstancesPage::default();
+        assert_eq!(page.get(2).unwrap().name, "Old");
+        assert_eq!(page.next_id(), 3);
+    }
+
+    #[test]
+    fn a_failed_copy_removes_only_its_own_folders() {
+        let tmp = tempfile::tempdir().unwrap();
+        paths::use_test_base_dir(tmp.path());
+        let activity = Activity::default();
+        let mut page = page();
+        let copy = inst(r#"{"id": 9, "name": "Alpha (copy)", "version": "1.19.8"}"#);
+        let ours = copy.data_dir();
+        let theirs = paths::instance_dir(None, "Alpha");
+        fs::create_dir_all(&ours).unwrap();
+        fs::create_dir_all(&theirs).unwrap();
+        page.instances.push(copy);
+        let worker = activity.start("copy");
+        page.copying.push((9, worker.task(), vec![ours.clone()]));
+        worker.post::<io::Result<()>>(Err(io::Error::other("disk full")));
+        drop(worker);
+
+        page.poll_copies(&activity, &Notifier::default());
+        assert!(page.get(9).is_none());
+        assert!(!ours.exists());
+        assert!(theirs.is_dir());
+    }
+
+    #[test]
+    fn delete_waits_for_confirmation() {
+        let tmp = tempfile::tempdir().unwrap();
+        paths::use_test_base_dir(tmp.path());
+        let (mut page, ctx) = (page(), harness::context());
+        let data = page.get(1).unwrap().data_dir();
+        assert!(data.starts_with(tmp.path()));
+        fs::create_dir_all(&data).unwrap();
+        page.pending_delete = Some(1);
+        frame(&mut page, &ctx, &[]);
+        frame(&mut page, &ctx, &[]);
+        assert!(page.get(1).is_some());
+        assert_eq!(page.pending_delete, Some(1));
+        assert!(data.is_dir());
+    }
+
+    #[test]
+    fn a_data_folder_two_instances_share_is_kept() {
+        let tmp = tempfile::tempdir().unwrap();
+        paths::use_test_base_dir(tmp.path());
+        let activity = Activity::default();
+        let mut page = page();
+        // lists saved before names were checked can hold the same name twice
+        page.instances.push(inst(r#"{"id": 4, "name": "Alpha", "version": "1.20.0"}"#));
+        let data = page.get(1).unwrap().data_dir();
+        fs::create_dir_all(&data).unwrap();
+
+        page.remove_instance(4, &activity, &Notifier::default());
+        assert!(page.get(4).is_none());
+        assert!(page.deleting.is_empty());
+        assert!(data.is_dir());
+    }
+
+    #[test]
+    fn new_instances_need_a_free_plain_name() {
+        let tmp = tempfile::tempdir().unwrap();
+        paths::use_test_base_dir(tmp.path());
+        let (mut page, notify) = (page(), Notifier::default());
+        page.new_version = "1.20.0".into();
+        for taken in ["Alpha", " Alpha ", "a/b", "..", "  "] {
+            page.new_name = taken.into();
+            assert!(page.create_instance(&notify).is_err(), "{taken:?}");
+        }
+        assert_eq!(page.instances.len(), 3);
+        assert!(!paths::instance_dir(None, "Alpha").exists());
+
+        page.new_name = " Delta ".into();
+        assert_eq!(page.create_instance(&notify).unwrap(), "Created Delta");
+        assert!(paths::instance_dir(None, "Delta").is_dir());
+    }
+
+    #[test]
+    fn failed_download_leaves_the_create_dialog_open() {
+        let mut page = page();
+        page.show_modal = true;
+        page.new_name = "Delta".into();
+        page.new_version = "0.0.0-never".into();
+        page.awaiting_version = Some(page.new_version.clone());
+        // nothing running or queued for it, and nothing installed
+        page.poll_awaited_version(&VersionPage::default(), &Notifier::default());
+        assert!(page.awaiting_version.is_none());
+        assert!(page.show_modal);
+        assert_eq!(page.instances.len(), 3);
     }
 }
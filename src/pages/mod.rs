@@ -2,3 +2,113 @@ pub mod home;
 pub mod versions;
 pub mod instances;
 pub mod mods;
+pub mod settings;
+#[cfg(test)]
+pub(crate) mod harness;
+
+use eframe::egui;
+use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
+
+/// Indices of `items` whose key fuzzy-matches `query` ("198" finds "1.19.8"),
+/// best match first; ties keep list order. An empty query keeps everything.
+pub(crate) fn fuzzy_filter<T, S: AsRef<str>>(query: &str, items: &[T], key: impl Fn(&T) -> S) -> Vec<usize> {
+    let query = query.trim();
+    if query.is_empty() {
+        return (0..items.len()).collect();
+    }
+    let matcher = SkimMatcherV2::default();
+    let mut scored: Vec<(i64, usize)> = items
+        .iter()
+        .enumerate()
+        .filter_map(|(i, item)| matcher.fuzzy_match(key(item).as_ref(), query).map(|s| (s, i)))
+        .collect();
+    scored.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+    scored.into_iter().map(|(_, i)| i).collect()
+}
+
+/// What the keyboard did to a list this frame.
+#[derive(PartialEq)]
+pub(crate) enum ListKey {
+    None,
+    /// highlight changed – scroll it into view
+    Moved,
+    /// Enter on the highlighted row
+    Activate,
+}
+
+/// Up/Down moves `selected` through `len` rows, Enter activates it.
+/// Ignored while a widget (e.g. a text field) has keyboard focus.
+pub(crate) fn list_keys(ctx: &egui::Context, len: usize, selected: &mut Option<usize>) -> ListKey {
+    if len == 0 {
+        *selected = None;
+        return ListKey::None;
+    }
+    if let Some(s) = selected {
+        *s = (*s).min(len - 1);
+    }
+    if ctx.memory(|m| m.focused().is_some()) {
+        return ListKey::None;
+    }
+    let (up, down, enter) = ctx.input(|i| {
+        (i.key_pressed(egui::Key::ArrowUp), i.key_pressed(egui::Key::ArrowDown), i.key_pressed(egui::Key::Enter))
+    });
+    if enter && selected.is_some() {
+        return ListKey::Activate;
+    }
+    let next = match (*selected, up, down) {
+        (None, true, _) | (None, _, true) => 0,
+        (Some(s), true, false) => s.saturating_sub(1),
+        (Some(s), false, true) => (s + 1).min(len - 1),
+        _ => return ListKey::None,
+    };
+    *selected = Some(next);
+    ListKey::Moved
+}
+
+#[cfg(test)]
+mod tests {
+    use eframe::egui::Key;
+
+    use super::{harness, *};
+
+    fn keys(ctx: &egui::Context, pressed: &[Key], len: usize, selected: &mut Option<usize>) -> ListKey {
+        let events = pressed.iter().map(|&k| harness::press(k)).collect();
+        harness::frame(ctx, events, |ctx| list_keys(ctx, len, selected))
+    }
+
+    #[test]
+    fn arrows_move_and_clamp_the_highlight() {
+        let ctx = harness::context();
+        let mut sel = None;
+        assert!(keys(&ctx, &[Key::ArrowDown], 3, &mut sel) == ListKey::Moved);
+        assert_eq!(sel, Some(0));
+        keys(&ctx, &[Key::ArrowDown], 3, &mut sel);
+        keys(&ctx, &[Key::ArrowDown], 3, &mut sel);
+        keys(&ctx, &[Key::ArrowDown], 3, &mut sel);
+        assert_eq!(sel, Some(2));
+        keys(&ctx, &[Key::ArrowUp], 3, &mut sel);
+        assert_eq!(sel, Some(1));
+        // the list shrank under the highlight
+        assert!(keys(&ctx, &[], 1, &mut sel) == ListKey::None);
+        assert_eq!(sel, Some(0));
+    }
+
+    #[test]
+    fn enter_activates_only_a_highlighted_row() {
+        let ctx = harness::context();
+        let mut sel = None;
+        assert!(keys(&ctx, &[Key::Enter], 3, &mut sel) == ListKey::None);
+        sel = Some(1);
+        assert!(keys(&ctx, &[Key::Enter], 3, &mut sel) == ListKey::Activate);
+        assert!(keys(&ctx, &[Key::Enter], 0, &mut sel) == ListKey::None);
+        assert_eq!(sel, None);
+    }
+
+    #[test]
+    fn fuzzy_filter_ranks_and_keeps_order_when_blank() {
+        let items = ["1.18.0", "1.19.8", "1.20.0"];
+        assert_eq!(fuzzy_filter("", &items, |s| *s), vec![0, 1, 2]);
+        assert_eq!(fuzzy_filter("198", &items, |s| *s), vec![1]);
+        assert!(fuzzy_filter("zzz", &items, |s| *s).is_empty());
+    }
+}
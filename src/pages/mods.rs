@@ -1,11 +1,34 @@
-use std::sync::mpsc::{channel, Receiver};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fs,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering as AtomicOrdering},
+        mpsc::{channel, Receiver},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
 
-use eframe::egui::{self, CentralPanel, ScrollArea};
-use reqwest::blocking::Client;
+use eframe::egui::{self, CentralPanel, ColorImage, ProgressBar, ScrollArea, TextureHandle, TextureOptions};
+use reqwest::Url;
 use serde::Deserialize;
 
+use crate::{
+    activity::{self, Activity, TaskId, TryRecvError},
+    config::Config,
+    modhistory::{Change, Entry, ModHistory},
+    modinfo,
+    modlock::ModLock,
+    net::{self, ApiError, Connectivity, ProgressEvent},
+    notify::Notifier,
+    disk,
+    pages::instances::{Instance, InstancesPage},
+    textures,
+};
+
 /*──────── data model ────────*/
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 struct ApiMod {
     #[serde(alias = "modid", alias = "id")]
     id: u32,
@@ -19,6 +42,247 @@ struct ApiMod {
     followcount: u32,
     #[serde(default)]
     commentcount: u32,
+    /// game versions the mod's releases are tagged for, e.g. "v1.19.8";
+    /// empty when the listing doesn't say
+    #[serde(alias = "gameversions", default)]
+    game_versions: Vec<String>,
+    /// thumbnail URL; empty when the mod has none
+    #[serde(default)]
+    logo: String,
+}
+
+/// One downloadable file of a mod, from `/api/mod/{id}`.
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct ApiRelease {
+    #[serde(default)]
+    pub(crate) modidstr: String,
+    #[serde(default)]
+    pub(crate) modversion: String,
+    /// game versions it's tagged for, e.g. "v1.19.8"
+    #[serde(default)]
+    pub(crate) tags: Vec<String>,
+    #[serde(default)]
+    pub(crate) created: String,
+    /// download URL of the release file
+    #[serde(default)]
+    pub(crate) mainfile: String,
+    #[serde(default)]
+    pub(crate) filename: String,
+}
+
+/// What the install worker sends back.
+enum InstallEvent {
+    Progress(ProgressEvent),
+    Done(Result<String, String>),
+}
+
+/// One running mod install.
+struct Install {
+    /// file being downloaded, for the progress row
+    file:     String,
+    /// instance it goes into
+    inst:     u64,
+    task:     TaskId,
+    cancel:   Arc<AtomicBool>,
+    /// bytes so far and the full size, if known
+    progress: (u64, Option<u64>),
+}
+
+/// The detail view's data for one mod.
+struct ModDetail {
+    id:       u32,
+    name:     String,
+    releases: Vec<ApiRelease>, // newest first
+}
+
+/// How a mod detail relates to the instance it would be installed into.
+struct InstanceView {
+    inst_id:   u64,
+    name:      String,
+    game:      String,
+    /// version of this mod found in the instance's mods folder
+    installed: Option<String>,
+    /// version pinned in the instance's modlock.json
+    pinned:    Option<String>,
+}
+
+/// What a pasted link points at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ModRef {
+    /// `/show/mod/{id}` on the mod DB
+    Id(u32),
+    /// `/{urlalias}` on the mod DB; the API takes it like a modid
+    Alias(String),
+    /// a mod file to download as-is, saved under `file`
+    File { url: String, file: String },
+}
+
+/// Sort choices: our key, the label, and the mod DB's `orderby` value
+/// (always descending).
+const SORTS: &[(&str, &str, &str)] = &[
+    ("latest", "Latest", "asset.created"),
+    ("downloads", "Most downloaded", "downloads"),
+    ("follows", "Most followed", "follows"),
+    ("comments", "Most commented", "comments"),
+];
+
+/// Mod DB tag ids of the game versions ("1.19.8" → tag id), fetched once
+/// for the version filter.
+static GAME_VERSION_TAGS: Mutex<Option<HashMap<String, i64>>> = Mutex::new(None);
+
+/// Quiet time after the last keystroke before the search box queries the API.
+const SEARCH_DEBOUNCE: Duration = Duration::from_millis(400);
+
+/// Mod DB paths that are pages of the site, not a mod's alias.
+const RESERVED_PATHS: &[&str] = &["api", "download", "files", "home", "list", "login", "logout", "notifications", "show", "tags"];
+
+/// Understands the links people share: a mod's page on the mod DB (by id or
+/// by its alias, with or without the scheme), a mod DB download link, or a
+/// `.zip` on any other host.
+pub(crate) fn parse_mod_url(text: &str) -> Option<ModRef> {
+    let text = text.trim();
+    let url = if text.contains("://") { Url::parse(text) } else { Url::parse(&format!("https://{text}")) }.ok()?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+    let host = url.host_str()?.trim_start_matches("www.");
+    let segments: Vec<&str> = url.path_segments()?.filter(|s| !s.is_empty()).collect();
+    let last_zip = segments.last().filter(|s| s.to_lowercase().ends_with(".zip")).map(|s| s.to_string());
+
+    if host != "mods.vintagestory.at" {
+        return last_zip.map(|file| ModRef::File { url: url.to_string(), file });
+    }
+    match segments.as_slice() {
+        ["show", "mod", id, ..] => id.parse().ok().map(ModRef::Id),
+        ["download" | "files", ..] => {
+            // `/download?fileid=N` names no file; the modinfo does once it's here
+            let fileid = url.query_pairs().find(|(k, _)| k == "fileid").map(|(_, v)| v.into_owned());
+            let file = last_zip.or_else(|| fileid.map(|id| format!("mod_{id}.zip")))?;
+            Some(ModRef::File { url: url.to_string(), file })
+        }
+        [alias] if !RESERVED_PATHS.contains(alias)
+            && alias.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') =>
+        {
+            Some(ModRef::Alias(alias.to_string()))
+        }
+        _ => None,
+    }
+}
+
+/*──────── page cache ────────*/
+type FetchResult = Result<(Vec<ApiMod>, usize), ApiError>;
+
+/// Everything that identifies one fetched page of results.
+#[derive(Clone, PartialEq, Eq, Debug)]
+struct FetchKey {
+    query:       String,
+    sort:        String,
+    gameversion: String,
+    page:        usize,
+    size:        usize,
+}
+
+/// Session-only LRU of fetched pages (front = least recently used).
+struct ModCache {
+    entries: VecDeque<(FetchKey, (Vec<ApiMod>, usize))>,
+}
+
+impl ModCache {
+    const CAPACITY: usize = 32;
+
+    fn get(&mut self, key: &FetchKey) -> Option<(Vec<ApiMod>, usize)> {
+        let pos = self.entries.iter().position(|(k, _)| k == key)?;
+        let entry = self.entries.remove(pos)?;
+        let hit = entry.1.clone();
+        self.entries.push_back(entry);
+        Some(hit)
+    }
+
+    fn insert(&mut self, key: FetchKey, value: (Vec<ApiMod>, usize)) {
+        self.entries.retain(|(k, _)| k != &key);
+        if self.entries.len() >= Self::CAPACITY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((key, value));
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+/// Logos downloading at once, at most.
+const THUMB_FETCHES: usize = 4;
+/// Logos are downscaled to this edge length before upload.
+const THUMB_SIZE: u32 = 96;
+
+/// Mod logos by mod id. A logo is only fetched while its cell is near the
+/// viewport, and only `THUMB_FETCHES` at a time; cells that miss a slot ask
+/// again on a later frame.
+struct Thumbnails {
+    /// `None` = failed; shows the placeholder for good
+    textures: HashMap<u32, Option<TextureHandle>>,
+    loading:  HashSet<u32>,
+    tx:       std::sync::mpsc::Sender<(u32, Option<ColorImage>)>,
+    rx:       Receiver<(u32, Option<ColorImage>)>,
+    /// runs on the worker thread; `fetch_logo` outside tests
+    fetch:    fn(&str) -> Option<ColorImage>,
+}
+
+impl Default for Thumbnails {
+    fn default() -> Self {
+        let (tx, rx) = channel();
+        Self { textures: HashMap::new(), loading: HashSet::new(), tx, rx, fetch: fetch_logo }
+    }
+}
+
+impl Thumbnails {
+    fn get(&self, id: u32) -> Option<&TextureHandle> {
+        self.textures.get(&id).and_then(Option::as_ref)
+    }
+
+    /// Starts fetching `url` for mod `id` unless it's known, in flight, or
+    /// all slots are busy. Returns whether a fetch started.
+    fn want(&mut self, id: u32, url: &str) -> bool {
+        if url.is_empty()
+            || self.textures.contains_key(&id)
+            || self.loading.contains(&id)
+            || self.loading.len() >= THUMB_FETCHES
+        {
+            return false;
+        }
+        self.loading.insert(id);
+        let (tx, url, fetch) = (self.tx.clone(), url.to_string(), self.fetch);
+        std::thread::spawn(move || {
+            let _ = tx.send((id, fetch(&url)));
+        });
+        true
+    }
+
+    /// Uploads finished logos; keeps repainting while any are in flight.
+    fn poll(&mut self, ctx: &egui::Context) {
+        while let Ok((id, img)) = self.rx.try_recv() {
+            self.loading.remove(&id);
+            let tex = img.map(|img| ctx.load_texture(format!("mod_logo_{id}"), img, TextureOptions::LINEAR));
+            self.textures.insert(id, tex);
+        }
+        if !self.loading.is_empty() {
+            ctx.request_repaint_after(Duration::from_millis(100));
+        }
+    }
+}
+
+/// Downloads a mod logo and scales it down to a thumbnail; `None` on any failure.
+fn fetch_logo(url: &str) -> Option<ColorImage> {
+    net::client()
+        .get(url)
+        .timeout(Duration::from_secs(20))
+        .send()
+        .and_then(|r| r.error_for_status())
+        .and_then(|r| r.bytes())
+        .ok()
+        .and_then(|bytes| image::load_from_memory(&bytes).ok())
+        .map(|img| textures::decode_image(img.thumbnail(THUMB_SIZE, THUMB_SIZE)))
 }
 
 /*──────── page state ────────*/
@@ -27,7 +291,51 @@ pub struct ModsPage {
     next_page: usize,
     total_pages: usize,
     loading: bool,
-    rx: Option<Receiver<Result<(Vec<ApiMod>, usize), String>>>,
+    task: Option<TaskId>,
+    pending: Option<FetchKey>,
+    cache: ModCache,
+    /// last listing fetch failure, shown above the grid
+    error: Option<String>,
+    /// rate-limited page to ask for again, and when
+    retry: Option<(Instant, FetchKey)>,
+    /// 429s in a row, for the backoff when there is no Retry-After
+    backoff: u32,
+
+    /// a first page was requested (automatically or by searching)
+    started:     bool,
+    /// keep fetching pages until the catalog is exhausted
+    prefetch:    bool,
+    /// user hit Stop: no more pages until Resume or a new search
+    paused:      bool,
+
+    /* detail window */
+    detail:      Option<ModDetail>,
+    detail_task: Option<TaskId>,
+    detail_err:  Option<String>,
+    detail_inst: Option<InstanceView>,
+    /// instance picked in the detail window; `None` = the footer's
+    target:      Option<u64>,
+    /// list releases for every game version, not just the target's
+    all_releases: bool,
+
+    /* pasted link and installs */
+    link:        String,
+    /// mod file from a pasted link, waiting for "Install"
+    link_file:   Option<(String, String)>,
+    /// running installs by modid (the file name for pasted links)
+    installs:    HashMap<String, Install>,
+    /// instances whose mods an install changed, for `take_changed`
+    changed:     Vec<u64>,
+
+    /* current listing parameters */
+    query:       String,
+    /// when the edited query is sent, unless more typing pushes it back
+    search_at:   Option<Instant>,
+    /// installed game versions for the filter and the badges; re-read on refresh
+    installed:   Option<Vec<String>>,
+    thumbs:      Thumbnails,
+    sort:        String,
+    gameversion: String,
 }
 
 impl Default for ModsPage {
@@ -37,121 +345,1133 @@ impl Default for ModsPage {
             next_page: 1,
             total_pages: 0,
             loading: false,
-            rx: None,
+            task: None,
+            pending: None,
+            cache: ModCache { entries: VecDeque::new() },
+            error: None,
+            retry: None,
+            backoff: 0,
+            prefetch: false,
+            paused: false,
+            started: false,
+            detail: None,
+            detail_task: None,
+            detail_err: None,
+            detail_inst: None,
+            target: None,
+            all_releases: false,
+            link: String::new(),
+            link_file: None,
+            installs: HashMap::new(),
+            changed: Vec::new(),
+            query: String::new(),
+            search_at: None,
+            installed: None,
+            thumbs: Thumbnails::default(),
+            sort: "latest".into(),
+            gameversion: String::new(),
         }
     }
 }
 
 /*──────── worker fetch ───────*/
-fn fetch_page(page: usize, size: usize) -> Result<(Vec<ApiMod>, usize), String> {
-    let url = format!(
-        "https://mods.vintagestory.at/api/mods?page={page}&pageSize={size}&sort=latest"
-    );
-    let json: serde_json::Value = Client::new()
-        .get(url)
-        .send()
-        .map_err(|e| e.to_string())?
-        .json()
-        .map_err(|e| e.to_string())?;
+/// `on_retry` hears about retries after connection failures.
+fn fetch_page(key: &FetchKey, on_retry: &mut dyn FnMut(u32)) -> FetchResult {
+    let FetchKey { query, sort, gameversion, page, size } = key;
+    let mut params = vec![
+        ("page", page.to_string()),
+        ("pageSize", size.to_string()),
+        ("orderby", order_by(sort).to_string()),
+        ("orderdirection", "desc".to_string()),
+    ];
+    if !query.is_empty() {
+        params.push(("text", query.clone()));
+    }
+    if !gameversion.is_empty() {
+        params.push(("gv[]", game_version_tag(gameversion)?.to_string()));
+    }
+    let json = net::get_json_retrying(net::client().get("https://mods.vintagestory.at/api/mods").query(&params), on_retry)?;
 
     let total_pages = json["totalPages"]
         .as_u64()
         .or_else(|| json["totalpages"].as_u64())
         .unwrap_or(1) as usize;
 
-    let mut mods: Vec<ApiMod> =
-        serde_json::from_value(json["mods"].clone()).map_err(|e| e.to_string())?;
-    mods.truncate(size); // safety cap
+    let entries = json["mods"]
+        .as_array()
+        .ok_or_else(|| "Unexpected JSON shape".to_string())?;
+    // one bad record shouldn't cost the whole page
+    let mut mods: Vec<ApiMod> = entries
+        .iter()
+        .filter_map(|e| serde_json::from_value(e.clone()).ok())
+        .collect();
+    let skipped = entries.len() - mods.len();
+    if skipped > 0 {
+        eprintln!("mods page {page}: skipped {skipped} malformed entries");
+    }
+    mods.truncate(*size); // safety cap
     Ok((mods, total_pages))
 }
 
+/// `/api/mod/{id}` (numeric id, modid string or url alias): numeric id,
+/// display name and releases, newest first.
+fn fetch_mod(id: &str) -> Result<(u32, String, Vec<ApiRelease>), String> {
+    let json = net::get_json(net::client().get(format!("https://mods.vintagestory.at/api/mod/{id}")))
+        .map_err(|e| e.to_string())?;
+    let m = &json["mod"];
+    if !m.is_object() {
+        return Err("Unexpected JSON shape".into());
+    }
+    let mut releases: Vec<ApiRelease> = m["releases"]
+        .as_array()
+        .map(|a| a.iter().filter_map(|r| serde_json::from_value(r.clone()).ok()).collect())
+        .unwrap_or_default();
+    releases.sort_by(|a, b| b.created.cmp(&a.created)); // "YYYY-MM-DD hh:mm:ss"
+    let num = m["assetid"].as_u64().or_else(|| m["modid"].as_u64()).or_else(|| id.parse().ok()).unwrap_or(0);
+    Ok((num as u32, m["name"].as_str().unwrap_or_default().to_string(), releases))
+}
+
+fn fetch_detail(key: &str) -> Result<ModDetail, String> {
+    let (id, name, releases) = fetch_mod(key)?;
+    Ok(ModDetail { id, name, releases })
+}
+
+/// The mod DB's `orderby` for sort key `sort`; unknown keys sort by latest.
+fn order_by(sort: &str) -> &'static str {
+    SORTS.iter().find(|(key, ..)| *key == sort).unwrap_or(&SORTS[0]).2
+}
+
+/// The mod DB's tag id for game version `version`, which is what its
+/// listing filters on.
+fn game_version_tag(version: &str) -> Result<i64, ApiError> {
+    let mut tags = GAME_VERSION_TAGS.lock().unwrap_or_else(|p| p.into_inner());
+    if tags.is_none() {
+        let json = net::get_json(net::client().get("https://mods.vintagestory.at/api/gameversions"))?;
+        let list = json["gameversions"]
+            .as_array()
+            .ok_or_else(|| ApiError::Failed("Unexpected JSON shape".into()))?;
+        let map = list
+            .iter()
+            .filter_map(|v| Some((v["name"].as_str()?.trim_start_matches('v').to_string(), v["tagid"].as_i64()?)))
+            .collect();
+        *tags = Some(map);
+    }
+    tags.as_ref()
+        .and_then(|t| t.get(version.trim_start_matches('v')).copied())
+        .ok_or_else(|| ApiError::Failed(format!("The mod DB doesn't know game version {version}")))
+}
+
+/// Newest of `installed` (newest first) that `m` lists, if the listing says.
+fn supported_installed<'a>(m: &ApiMod, installed: &'a [String]) -> Option<&'a str> {
+    installed
+        .iter()
+        .find(|ver| m.game_versions.iter().any(|g| g.trim_start_matches('v') == ver.as_str()))
+        .map(String::as_str)
+}
+
+/// Releases of the mod with this modid, newest first.
+pub(crate) fn fetch_releases(modid: &str) -> Result<Vec<ApiRelease>, String> {
+    fetch_mod(modid).map(|(_, _, releases)| releases)
+}
+
+/// The modid `release` belongs to; the numeric `id` of its mod when the
+/// API left the string out.
+fn release_modid(release: &ApiRelease, id: u32) -> String {
+    if release.modidstr.is_empty() { id.to_string() } else { release.modidstr.clone() }
+}
+
+/// File name to store `release` of `modid` under: the API's name when it
+/// is usable, else `{modid}_{version}.zip`. Always a bare name, so both the
+/// installer and the updater can join it onto the mods folder.
+pub(crate) fn release_file(release: &ApiRelease, modid: &str) -> String {
+    match safe_file_name(&release.filename) {
+        Some(name) => name.to_string(),
+        None => {
+            // both parts come from outside too; keep them to plain characters
+            let plain = |s: &str| -> String {
+                s.replace("..", "_")
+                    .chars()
+                    .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') { c } else { '_' })
+                    .collect()
+            };
+            format!("{}_{}.zip", plain(modid), plain(&release.modversion))
+        }
+    }
+}
+
+/// `name` reduced to its last component, if that stays inside the folder
+/// it gets joined to: not absolute, not empty, no `..` and no separators
+/// of either platform. The name comes from the mod DB, so never trust it
+/// with the path.
+fn safe_file_name(name: &str) -> Option<&str> {
+    let path = Path::new(name);
+    if path.is_absolute() || name.starts_with(['/', '\\']) || name.contains("..") {
+        return None;
+    }
+    let base = path.file_name()?.to_str()?;
+    (!base.trim().is_empty() && !base.contains(['/', '\\'])).then_some(base)
+}
+
+/// Downloads the mod at `url` into `inst`'s mods folder as `file` and
+/// records the install in its history. Blocking; progress goes to `emit`.
+/// Files that turn out not to be a mod are removed again.
+pub(crate) fn install_file(
+    inst: &Instance,
+    url: &str,
+    file: &str,
+    cancel: &AtomicBool,
+    emit: &mut dyn FnMut(ProgressEvent),
+) -> Result<String, String> {
+    let dir = inst.mods_dir();
+    let dest = dir.join(file);
+    if dest.exists() {
+        return Err(format!("{file} is already in {}", inst.name));
+    }
+    let part = dir.join(format!("{file}.part"));
+    net::download_file_with_progress(url, &part, cancel, emit).map_err(|e| {
+        let _ = fs::remove_file(&part);
+        e.to_string()
+    })?;
+    // cancelled after the last chunk: still leave nothing behind
+    if cancel.load(AtomicOrdering::Relaxed) {
+        let _ = fs::remove_file(&part);
+        return Err("cancelled".into());
+    }
+    fs::rename(&part, &dest).map_err(|e| e.to_string())?;
+    match modinfo::read_modinfo(&dest) {
+        Ok(info) => {
+            let _ = ModHistory::record(inst, Entry::new(Change::Installed, &info));
+            Ok(format!("Installed {} {} into {}", info.name, info.version, inst.name))
+        }
+        Err(_) => {
+            let _ = fs::remove_file(&dest);
+            Err(format!("{file} is not a Vintage Story mod"))
+        }
+    }
+}
+
+/// Does the release list `game` (e.g. "1.19.8") among its tags?
+fn supports(release: &ApiRelease, game: &str) -> bool {
+    release.tags.iter().any(|t| t.trim_start_matches('v') == game)
+}
+
+/// First release in `releases` (newest first) that supports `game`.
+pub(crate) fn newest_compatible<'a>(releases: &'a [ApiRelease], game: &str) -> Option<&'a ApiRelease> {
+    releases.iter().find(|r| supports(r, game))
+}
+
 /*──────── egui UI ───────────*/
 impl ModsPage {
-    pub fn ui(&mut self, ctx: &egui::Context) {
-        /* first run — load 50 */
-        if self.mods.is_empty() && !self.loading {
-            self.start_fetch(1, 96);
+    /// `selected` is the footer's instance; the detail window can pick
+    /// another from `instances`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn ui(
+        &mut self,
+        ctx: &egui::Context,
+        activity: &Activity,
+        notify: &Notifier,
+        net: Connectivity,
+        config: &mut Config,
+        instances: &InstancesPage,
+        selected: Option<&Instance>,
+    ) {
+        let page_size = config.mods_page_size;
+        /* first run — once we know we're online, unless the user opted out */
+        if !self.started && config.mods_auto_load && net.is_online() {
+            self.refresh(page_size, activity);
         }
 
         /* poll worker */
-        if let Some(rx) = &self.rx {
-            if let Ok(result) = rx.try_recv() {
+        if let Some(task) = self.task {
+            let result = match activity.try_take::<FetchResult>(task) {
+                Ok(result) => Some(result),
+                Err(TryRecvError::Empty) => None,
+                Err(TryRecvError::Disconnected) => {
+                    Some(Err(ApiError::Failed("Mod list worker stopped unexpectedly".into())))
+                }
+            };
+            if let Some(result) = result {
                 self.loading = false;
-                self.rx = None;
-                if let Ok((mut mods, total)) = result {
-                    self.total_pages = total;
-                    self.mods.append(&mut mods);
-                    // next_page already bumped in start_fetch
+                self.task = None;
+                match result {
+                    Ok((mut mods, total)) => {
+                        self.backoff = 0;
+                        if let Some(key) = self.pending.take() {
+                            self.cache.insert(key, (mods.clone(), total));
+                        }
+                        self.total_pages = total;
+                        self.mods.append(&mut mods);
+                        // next_page already bumped in start_fetch
+                    }
+                    Err(ApiError::RateLimited(after)) => {
+                        // 1, 2, 4 … 32 s unless the server said otherwise
+                        let wait = after.unwrap_or(Duration::from_secs(1 << self.backoff.min(5)));
+                        self.backoff += 1;
+                        if let Some(key) = self.pending.take() {
+                            self.retry = Some((Instant::now() + wait, key));
+                            self.loading = true; // holds off scroll loading and prefetch meanwhile
+                        }
+                    }
+                    Err(ApiError::Failed(e)) => {
+                        self.pending = None;
+                        self.prefetch = false;
+                        notify.error(format!("Could not load mods: {e}"));
+                        self.error = Some(e);
+                    }
                 }
             }
         }
+        if let Some((at, key)) = &self.retry {
+            if Instant::now() >= *at {
+                let (page, size) = (key.page, key.size);
+                self.retry = None;
+                self.loading = false;
+                self.start_fetch(page, size, activity);
+            } else {
+                ctx.request_repaint_after(Duration::from_millis(250));
+            }
+        }
+
+        if self.prefetch && !self.paused && !self.loading && !net.is_offline() {
+            if self.more_pages() {
+                self.start_fetch(self.next_page, page_size, activity);
+            } else {
+                self.prefetch = false;
+            }
+        }
+
+        if self.search_at.is_some() && !net.is_offline() {
+            if self.search_due(Instant::now()) {
+                self.refresh(page_size, activity);
+            } else {
+                ctx.request_repaint_after(SEARCH_DEBOUNCE);
+            }
+        }
+
+        self.thumbs.poll(ctx);
+        self.poll_install(activity, notify);
+        self.poll_detail(activity, self.target.and_then(|id| instances.get(id)).or(selected));
+        let mut open_detail = None;
 
         CentralPanel::default().show(ctx, |ui| {
+            if net.is_offline() {
+                ui.colored_label(ui.visuals().warn_fg_color, "Offline — showing cached data");
+            }
+            ui.horizontal(|ui| {
+                ui.label("Search:");
+                let edit = ui.add(egui::TextEdit::singleline(&mut self.query).hint_text("Mod name, author, …"));
+                if edit.changed() {
+                    self.typed(Instant::now());
+                }
+                let submitted = edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                let can_search = !net.is_offline();
+                if (ui.add_enabled(can_search, egui::Button::new("Search")).clicked() || submitted)
+                    && can_search
+                {
+                    self.search_at = None;
+                    self.refresh(page_size, activity);
+                }
+                if !self.query.is_empty() && ui.small_button("✖").on_hover_text("Back to the latest mods").clicked() {
+                    self.query.clear();
+                    self.search_at = None;
+                    self.refresh(page_size, activity);
+                }
+                ui.separator();
+                ui.label("Sort:");
+                let was = self.sort.clone();
+                let current = SORTS.iter().find(|(key, ..)| *key == was).unwrap_or(&SORTS[0]).1;
+                egui::ComboBox::from_id_source("mods_sort").selected_text(current).show_ui(ui, |ui| {
+                    for (key, label, _) in SORTS {
+                        ui.selectable_value(&mut self.sort, key.to_string(), *label);
+                    }
+                });
+                if self.sort != was {
+                    if net.is_offline() {
+                        self.sort = was;
+                    } else {
+                        self.refresh(page_size, activity);
+                    }
+                }
+                ui.separator();
+                ui.label("Game version:");
+                let installed = self.installed.get_or_insert_with(InstancesPage::installed_versions).clone();
+                let was = self.gameversion.clone();
+                egui::ComboBox::from_id_source("mods_gameversion")
+                    .selected_text(if was.is_empty() { "Any".to_string() } else { format!("v{was}") })
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.gameversion, String::new(), "Any");
+                        for ver in &installed {
+                            ui.selectable_value(&mut self.gameversion, ver.clone(), format!("v{ver}"));
+                        }
+                    })
+                    .response
+                    .on_hover_text("Only mods with a release for this installed version");
+                if self.gameversion != was && !net.is_offline() {
+                    self.refresh(page_size, activity);
+                } else if net.is_offline() {
+                    self.gameversion = was;
+                }
+            });
+            self.link_ui(ui, activity, notify, selected);
+            self.install_progress_ui(ui);
+            if !self.started {
+                // auto-loading is off in the settings: nothing is fetched until asked
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new("The mod list loads on request").weak());
+                    if ui.add_enabled(!net.is_offline(), egui::Button::new("Load mods")).clicked() {
+                        self.refresh(page_size, activity);
+                    }
+                });
+                return;
+            }
             ui.horizontal(|ui| {
                 ui.label(format!("Loaded {}", self.mods.len()));
-                if self.loading {
+                if let Some((at, _)) = &self.retry {
+                    let secs = at.saturating_duration_since(Instant::now()).as_secs() + 1;
+                    ui.colored_label(ui.visuals().warn_fg_color, format!("Rate limited — retrying in {secs}s"));
+                } else if self.loading {
                     ui.spinner();
                 }
+                let can_refresh = !self.loading && !net.is_offline();
+                if ui.add_enabled(can_refresh, egui::Button::new("Refresh")).clicked() {
+                    self.cache.clear();
+                    self.refresh(page_size, activity);
+                }
+                ui.separator();
+                let was = config.mods_compact;
+                ui.selectable_value(&mut config.mods_compact, false, "▦ Grid");
+                ui.selectable_value(&mut config.mods_compact, true, "☰ List");
+                if config.mods_compact != was {
+                    if let Err(e) = config.save() {
+                        notify.error(format!("Could not save settings: {e}"));
+                    }
+                }
             });
+            self.progress_ui(ui);
             ui.separator();
 
+            /* nothing to show: say why */
+            if self.mods.is_empty() {
+                ui.add_space(24.0);
+                ui.vertical_centered(|ui| {
+                    if self.loading {
+                        ui.spinner();
+                        ui.label("Loading mods…");
+                    } else if self.error.is_some() {
+                        ui.label("Couldn't load the mod list.");
+                        if ui.add_enabled(!net.is_offline(), egui::Button::new("Try again")).clicked() {
+                            self.refresh(page_size, activity);
+                        }
+                    } else {
+                        let query = self.query.trim();
+                        ui.heading(if query.is_empty() {
+                            "No mods found".to_string()
+                        } else {
+                            format!("No mods match \"{query}\"")
+                        });
+                        ui.label(egui::RichText::new("Try fewer or broader words, or check the spelling.").weak());
+                        if !query.is_empty()
+                            && ui.add_enabled(!net.is_offline(), egui::Button::new("Clear search")).clicked()
+                        {
+                            self.query.clear();
+                            self.refresh(page_size, activity);
+                        }
+                    }
+                });
+                return;
+            }
+
+            let compact = config.mods_compact;
+            let installed = self.installed.clone().unwrap_or_default();
             ScrollArea::both().show(ui, |ui| {
-                egui::Grid::new("mods_grid")
-                    .num_columns(4)
-                    .spacing([16.0, 16.0])
-                    .show(ui, |ui| {
-                        let mut need_more = false;
+                let grid = if compact {
+                    egui::Grid::new("mods_list").num_columns(4).striped(true).spacing([24.0, 4.0])
+                } else {
+                    egui::Grid::new("mods_grid").num_columns(4).spacing([16.0, 16.0])
+                };
+                grid.show(ui, |ui| {
+                    let mut need_more = false;
 
-                        for (i, m) in self.mods.iter().enumerate() {
+                    for (i, m) in self.mods.iter().enumerate() {
+                        if compact {
+                            /* ----- render row ----- */
+                            if Self::title_label(ui, m).clicked() {
+                                open_detail = Some(m.id.to_string());
+                            }
+                            ui.label(egui::RichText::new(&m.authorname).small());
+                            ui.label(egui::RichText::new(format!("⬇ {}", m.downloadcount)).small());
+                            Self::compat_badge(ui, m, &self.gameversion, &installed);
+                            ui.end_row();
+                        } else {
                             /* ----- render cell ----- */
-                            ui.vertical(|ui| {
-                                let title = if m.displayname.is_empty() {
-                                    format!("ID {}", m.id)
-                                } else {
-                                    m.displayname.clone()
-                                };
-                                ui.label(egui::RichText::new(title).strong());
-                                if !m.authorname.is_empty() {
-                                    ui.label(egui::RichText::new(&m.authorname).small());
+                            ui.horizontal_top(|ui| {
+                                let logo = textures::avatar(ui, self.thumbs.get(m.id), &m.displayname, 48.0);
+                                // placeholders just past the edges start loading too
+                                if !net.is_offline() && ui.is_rect_visible(logo.rect.expand(logo.rect.height() * 4.0)) {
+                                    self.thumbs.want(m.id, &m.logo);
                                 }
-                                ui.label(
-                                    egui::RichText::new(format!(
-                                        "⬇ {}  👥 {}  💬 {}",
-                                        m.downloadcount, m.followcount, m.commentcount
-                                    ))
-                                    .small(),
-                                );
+                                ui.vertical(|ui| {
+                                    if Self::title_label(ui, m).clicked() {
+                                        open_detail = Some(m.id.to_string());
+                                    }
+                                    if !m.authorname.is_empty() {
+                                        ui.label(egui::RichText::new(&m.authorname).small());
+                                    }
+                                    ui.label(
+                                        egui::RichText::new(format!(
+                                            "⬇ {}  👥 {}  💬 {}",
+                                            m.downloadcount, m.followcount, m.commentcount
+                                        ))
+                                        .small(),
+                                    );
+                                    Self::compat_badge(ui, m, &self.gameversion, &installed);
+                                });
                             });
 
                             if (i + 1) % 4 == 0 {
                                 ui.end_row();
                             }
-
-                            /* mark if we've reached 80 % of current list */
-                            if !self.loading
-                                && self.next_page <= self.total_pages
-                                && i >= self.mods.len() * 4 / 5  // 80 %
-                            {
-                                need_more = true;
-                            }
                         }
 
-                        /* after grid draw = safe mut-borrow */
-                        if need_more {
-                            self.start_fetch(self.next_page, 24);
+                        /* mark if we've reached 80 % of current list */
+                        if !self.loading
+                            && !self.paused
+                            && !net.is_offline()
+                            && self.more_pages()
+                            && i >= self.mods.len() * 4 / 5  // 80 %
+                        {
+                            need_more = true;
                         }
-                    });
+                    }
+
+                    /* after grid draw = safe mut-borrow */
+                    if need_more {
+                        self.start_fetch(self.next_page, page_size, activity);
+                    }
+                });
             });
         });
+
+        if let Some(id) = open_detail {
+            self.open_detail(id, activity);
+        }
+        let install = self.detail_window(ctx, activity, instances);
+        let target = self.target.and_then(|id| instances.get(id)).or(selected);
+        if let (Some(release), Some(inst)) = (install, target) {
+            let modid = release_modid(&release, self.detail.as_ref().map_or(0, |d| d.id));
+            let file = release_file(&release, &modid);
+            self.start_install(modid, inst, release.mainfile, file, activity);
+        }
     }
 
-    fn start_fetch(&mut self, page: usize, size: usize) {
-        self.loading = true;
-        let (tx, rx) = channel();
-        self.rx = Some(rx);
+    /// "Paste a link" row: mod pages open their detail window, mod files
+    /// are offered for the footer's instance.
+    fn link_ui(&mut self, ui: &mut egui::Ui, activity: &Activity, notify: &Notifier, selected: Option<&Instance>) {
+        ui.horizontal(|ui| {
+            ui.label("Link:");
+            let edit = ui.add(
+                egui::TextEdit::singleline(&mut self.link).hint_text("Paste a mods.vintagestory.at or .zip link"),
+            );
+            let submitted = edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if (ui.add_enabled(!self.link.trim().is_empty(), egui::Button::new("Open")).clicked() || submitted)
+                && !self.link.trim().is_empty()
+            {
+                self.link_file = None;
+                match parse_mod_url(&self.link) {
+                    Some(ModRef::Id(id)) => self.open_detail(id.to_string(), activity),
+                    Some(ModRef::Alias(alias)) => self.open_detail(alias, activity),
+                    Some(ModRef::File { url, file }) => self.link_file = Some((url, file)),
+                    None => notify.error("That isn't a mod DB page or a mod file link"),
+                }
+            }
+        });
+        let Some((url, file)) = self.link_file.clone() else { return };
+        ui.horizontal(|ui| {
+            ui.label(format!("📦 {file}"));
+            let label = match selected {
+                Some(inst) => format!("⬇ Install into {}", inst.name),
+                None => "⬇ Install".to_string(),
+            };
+            let can = selected.is_some() && !self.installs.contains_key(&file);
+            let resp = ui.add_enabled(can, egui::Button::new(label));
+            let resp = if selected.is_none() { resp.on_disabled_hover_text("Select an instance in the footer") } else { resp };
+            if resp.clicked() {
+                if let Some(inst) = selected {
+                    self.start_install(file.clone(), inst, url, file, activity);
+                    self.link_file = None;
+                    self.link.clear();
+                }
+            }
+            if ui.small_button("✖").clicked() {
+                self.link_file = None;
+            }
+        });
+    }
+
+    /// Installs `file` from `url` into `inst` in the background, tracked
+    /// under `key` so the same mod isn't installed twice at once.
+    fn start_install(&mut self, key: String, inst: &Instance, url: String, file: String, activity: &Activity) {
+        if self.installs.contains_key(&key) {
+            return;
+        }
+        let inst = inst.clone();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let guard = activity.start(format!("Installing {file} into {}", inst.name));
+        guard.set_cancel(cancel.clone());
+        let install = Install { file: file.clone(), inst: inst.id, task: guard.task(), cancel: cancel.clone(), progress: (0, None) };
+        self.installs.insert(key, install);
         std::thread::spawn(move || {
-            let _ = tx.send(fetch_page(page, size));
+            let result = activity::catch_panic(|| {
+                install_file(&inst, &url, &file, &cancel, &mut |ev| {
+                    if let ProgressEvent::Progress(frac) = ev {
+                        guard.set_progress(frac);
+                    }
+                    guard.post(InstallEvent::Progress(ev));
+                })
+            });
+            guard.post(InstallEvent::Done(result));
         });
+    }
+
+    fn poll_install(&mut self, activity: &Activity, notify: &Notifier) {
+        let mut done = Vec::new();
+        for (key, install) in &mut self.installs {
+            let result = loop {
+                match activity.try_take(install.task) {
+                    Ok(InstallEvent::Progress(ProgressEvent::Transfer { done, total, .. })) => {
+                        install.progress = (done, total);
+                    }
+                    Ok(InstallEvent::Progress(_)) => {}
+                    Ok(InstallEvent::Done(result)) => break Some(result),
+                    Err(TryRecvError::Empty) => break None,
+                    Err(TryRecvError::Disconnected) => break Some(Err("Install worker stopped unexpectedly".into())),
+                }
+            };
+            if let Some(result) = result {
+                done.push((key.clone(), result));
+            }
+        }
+        for (key, result) in done {
+            let Some(install) = self.installs.remove(&key) else { continue };
+            if install.cancel.load(AtomicOrdering::Relaxed) {
+                notify.success(format!("Install of {} cancelled", install.file));
+            } else {
+                if result.is_ok() {
+                    self.changed.push(install.inst);
+                }
+                notify.result(result);
+            }
+            self.detail_inst = None; // re-read what the instance has
+        }
+    }
+
+    /// Instances that gained a mod since the last call, so their cached
+    /// mod lists (and dependency warnings) can be refreshed.
+    pub fn take_changed(&mut self) -> Vec<u64> {
+        std::mem::take(&mut self.changed)
+    }
+
+    /// "1.2 MB of 4.8 MB" bar with a Cancel button per running install.
+    fn install_progress_ui(&self, ui: &mut egui::Ui) {
+        if self.installs.is_empty() {
+            return;
+        }
+        let mut rows: Vec<&Install> = self.installs.values().collect();
+        rows.sort_by(|a, b| a.file.cmp(&b.file));
+        for install in rows {
+            ui.horizontal(|ui| {
+                let cancelling = install.cancel.load(AtomicOrdering::Relaxed);
+                if ui.add_enabled(!cancelling, egui::Button::new("Cancel")).clicked() {
+                    install.cancel.store(true, AtomicOrdering::Relaxed);
+                }
+                let (done, total) = install.progress;
+                let bar = match total {
+                    Some(total) => ProgressBar::new(done as f32 / total as f32)
+                        .text(format!("{}: {} of {}", install.file, disk::human_bytes(done), disk::human_bytes(total))),
+                    None => ProgressBar::new(0.0)
+                        .animate(true)
+                        .text(format!("{}: {}", install.file, disk::human_bytes(done))),
+                };
+                ui.add(bar);
+            });
+        }
+        ui.ctx().request_repaint_after(Duration::from_millis(100));
+    }
+
+    /// "✔ v1.19.8" when `m` works with the filtered or an installed game
+    /// version; nothing when the listing doesn't say which versions it supports.
+    fn compat_badge(ui: &mut egui::Ui, m: &ApiMod, filter: &str, installed: &[String]) {
+        let good = egui::Color32::from_rgb(90, 170, 90);
+        if !filter.is_empty() {
+            ui.colored_label(good, egui::RichText::new(format!("✔ v{filter}")).small());
+        } else if let Some(ver) = supported_installed(m, installed) {
+            ui.colored_label(good, egui::RichText::new(format!("✔ v{ver}")).small());
+        } else if !m.game_versions.is_empty() {
+            ui.label(egui::RichText::new("✖ no installed version").small().weak())
+                .on_hover_text(m.game_versions.join(", "));
+        } else {
+            ui.label("");
+        }
+    }
+
+    /// Clickable mod name (falls back to the id); opens the detail window.
+    fn title_label(ui: &mut egui::Ui, m: &ApiMod) -> egui::Response {
+        let title = if m.displayname.is_empty() {
+            format!("ID {}", m.id)
+        } else {
+            m.displayname.clone()
+        };
+        ui.add(egui::Label::new(egui::RichText::new(title).strong()).sense(egui::Sense::click()))
+            .on_hover_text("Click for releases")
+    }
+
+    /// `id` is anything `/api/mod/{id}` takes: numeric id, modid or url alias.
+    fn open_detail(&mut self, id: String, activity: &Activity) {
+        self.detail = None;
+        self.detail_err = None;
+        self.detail_inst = None;
+        if let Some(stale) = self.detail_task.take() {
+            activity.forget(stale);
+        }
+        self.detail_task = Some(activity.spawn("Loading mod details", move |task| {
+            task.post(activity::catch_panic(|| fetch_detail(&id)));
+        }));
+    }
+
+    /// Collects the detail fetch and keeps the installed-version lookup in
+    /// step with the target instance (re-read only when that changes).
+    fn poll_detail(&mut self, activity: &Activity, selected: Option<&Instance>) {
+        if let Some(task) = self.detail_task {
+            let result: Option<Result<ModDetail, String>> = match activity.try_take(task) {
+                Ok(result) => Some(result),
+                Err(TryRecvError::Empty) => None,
+                Err(TryRecvError::Disconnected) => Some(Err("Detail worker stopped unexpectedly".into())),
+            };
+            if let Some(result) = result {
+                self.detail_task = None;
+                match result {
+                    Ok(d) => self.detail = Some(d),
+                    Err(e) => self.detail_err = Some(e),
+                }
+            }
+        }
+        let Some(detail) = &self.detail else { return };
+        let Some(inst) = selected else {
+            self.detail_inst = None;
+            return;
+        };
+        if self.detail_inst.as_ref().is_some_and(|v| v.inst_id == inst.id && v.game == inst.version) {
+            return;
+        }
+        let modids: Vec<String> = detail.releases.iter().map(|r| r.modidstr.to_lowercase()).collect();
+        let installed = modinfo::list_mods(&inst.mods_dir())
+            .into_iter()
+            .filter_map(|m| m.info.ok())
+            .find(|info| modids.contains(&info.modid.to_lowercase()))
+            .map(|info| info.version);
+        let lock = ModLock::load(inst);
+        let pinned = modids.iter().find_map(|id| lock.pinned(id)).map(str::to_owned);
+        self.detail_inst = Some(InstanceView {
+            inst_id: inst.id,
+            name: inst.name.clone(),
+            game: inst.version.clone(),
+            installed,
+            pinned,
+        });
+    }
+
+    /// Returns the release the user asked to install into the target instance.
+    fn detail_window(&mut self, ctx: &egui::Context, activity: &Activity, instances: &InstancesPage) -> Option<ApiRelease> {
+        if self.detail.is_none() && self.detail_task.is_none() && self.detail_err.is_none() {
+            return None;
+        }
+        let mut open = true;
+        let mut install = None;
+        let mut pick = None;
+        let installing = self.detail.as_ref().is_some_and(|d| {
+            d.releases.iter().any(|r| self.installs.contains_key(&release_modid(r, d.id)))
+        });
+        let title = self.detail.as_ref().map(|d| d.name.clone()).unwrap_or_else(|| "Mod".into());
+        egui::Window::new(title)
+            .id(egui::Id::new("mod_detail"))
+            .open(&mut open)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                if let Some(e) = &self.detail_err {
+                    ui.colored_label(ui.visuals().warn_fg_color, format!("Could not load mod: {e}"));
+                    return;
+                }
+                let Some(d) = &self.detail else {
+                    ui.spinner();
+                    return;
+                };
+                let view = self.detail_inst.as_ref();
+                let newest = view.and_then(|v| newest_compatible(&d.releases, &v.game));
+
+                ui.horizontal(|ui| {
+                    ui.label("Instance:");
+                    egui::ComboBox::from_id_source("mod_detail_target")
+                        .selected_text(view.map_or("Select…", |v| v.name.as_str()))
+                        .show_ui(ui, |ui| {
+                            for idx in instances.ordered("") {
+                                let inst = &instances.instances[idx];
+                                let current = view.is_some_and(|v| v.inst_id == inst.id);
+                                if ui.selectable_label(current, format!("{} (v{})", inst.name, inst.version)).clicked() {
+                                    pick = Some(inst.id);
+                                }
+                            }
+                        });
+                });
+
+                /* summary: you have X, latest is Y */
+                match view {
+                    None => {
+                        ui.label(egui::RichText::new("Pick an instance to compare and install").weak());
+                    }
+                    Some(v) => {
+                        match &v.installed {
+                            Some(have) => ui.label(format!("{} has {have}", v.name)),
+                            None => ui.label(format!("Not installed in {}", v.name)),
+                        };
+                        match newest {
+                            _ if v.pinned.is_some() => {
+                                ui.label(format!("🔒 Pinned to {} in modlock.json", v.pinned.as_deref().unwrap_or("")));
+                            }
+                            Some(r) if v.installed.as_deref() == Some(r.modversion.as_str()) => {
+                                ui.label(format!("Up to date for game v{}", v.game));
+                            }
+                            Some(r) => {
+                                ui.strong(format!("Newest for game v{}: {}", v.game, r.modversion));
+                                if v.installed.is_none()
+                                    && !r.mainfile.is_empty()
+                                    && ui
+                                        .add_enabled(!installing, egui::Button::new(format!("⬇ Install into {}", v.name)))
+                                        .clicked()
+                                {
+                                    install = Some(r.clone());
+                                }
+                            }
+                            None => {
+                                ui.colored_label(
+                                    ui.visuals().warn_fg_color,
+                                    format!("No release is tagged for game v{}", v.game),
+                                );
+                            }
+                        }
+                    }
+                }
+                self.install_progress_ui(ui);
+                ui.separator();
+
+                if let Some(v) = view {
+                    ui.checkbox(&mut self.all_releases, format!("Show releases for other game versions than v{}", v.game));
+                }
+                ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                    let shown: Vec<&ApiRelease> = d
+                        .releases
+                        .iter()
+                        .filter(|r| self.all_releases || view.is_none_or(|v| supports(r, &v.game)))
+                        .collect();
+                    if shown.is_empty() {
+                        ui.label("No releases");
+                    }
+                    for r in shown {
+                        let installed = view.and_then(|v| v.installed.as_deref()) == Some(r.modversion.as_str());
+                        let is_newest = newest.is_some_and(|n| std::ptr::eq(n, r));
+                        ui.horizontal(|ui| {
+                            let text = egui::RichText::new(&r.modversion);
+                            ui.label(if installed || is_newest { text.strong() } else { text });
+                            if installed {
+                                ui.colored_label(egui::Color32::from_rgb(90, 170, 90), "✔ installed");
+                            }
+                            if is_newest {
+                                ui.label("★ newest compatible");
+                            }
+                            ui.label(egui::RichText::new(r.tags.join(", ")).small().weak());
+                            // another version already there is swapped by updating, not here
+                            if let Some(v) = view.filter(|v| v.installed.is_none() && supports(r, &v.game)) {
+                                let button = ui.add_enabled(!installing && !r.mainfile.is_empty(), egui::Button::new("⬇").small());
+                                if button.on_hover_text(format!("Install {} into {}", r.modversion, v.name)).clicked() {
+                                    install = Some(r.clone());
+                                }
+                            }
+                        });
+                    }
+                });
+                ui.hyperlink_to("Open on the mod DB", format!("https://mods.vintagestory.at/show/mod/{}", d.id));
+            });
+        if let Some(id) = pick {
+            self.target = Some(id);
+        }
+        if !open {
+            self.detail = None;
+            if let Some(task) = self.detail_task.take() {
+                activity.forget(task);
+            }
+            self.detail_err = None;
+            self.detail_inst = None;
+            self.target = None;
+        }
+        install
+    }
+
+    fn more_pages(&self) -> bool {
+        self.next_page <= self.total_pages
+    }
+
+    /// "Loaded 5 of 142 pages" with Fetch all / Stop / Resume.
+    fn progress_ui(&mut self, ui: &mut egui::Ui) {
+        if self.total_pages <= 1 {
+            return;
+        }
+        let loaded = (self.next_page - 1 - self.loading as usize).min(self.total_pages);
+        ui.horizontal(|ui| {
+            ui.add(
+                ProgressBar::new(loaded as f32 / self.total_pages as f32)
+                    .desired_width(200.0)
+                    .text(format!("loaded {loaded} of {} pages", self.total_pages)),
+            );
+            if !self.more_pages() {
+                return;
+            }
+            if self.paused {
+                if ui.button("▶ Resume").on_hover_text("Load more pages while scrolling").clicked() {
+                    self.paused = false;
+                }
+            } else {
+                if !self.prefetch
+                    && ui.button("⬇ Fetch all").on_hover_text("Keep loading pages until the whole catalog is here").clicked()
+                {
+                    self.prefetch = true;
+                }
+                if ui.button("⏹ Stop").on_hover_text("Stop loading further pages").clicked() {
+                    self.paused = true;
+                    self.prefetch = false;
+                }
+            }
+        });
+    }
+
+    /// The query was edited at `now`: (re)starts the debounce.
+    fn typed(&mut self, now: Instant) {
+        self.search_at = Some(now + SEARCH_DEBOUNCE);
+    }
+
+    /// Whether the debounced search should go out at `now`; it goes once.
+    fn search_due(&mut self, now: Instant) -> bool {
+        let due = self.search_at.is_some_and(|at| now >= at);
+        if due {
+            self.search_at = None;
+        }
+        due
+    }
+
+    fn start_fetch(&mut self, page: usize, size: usize, activity: &Activity) {
+        let key = FetchKey {
+            query: self.query.trim().to_string(),
+            sort: self.sort.clone(),
+            gameversion: self.gameversion.clone(),
+            page,
+            size,
+        };
         self.next_page = page + 1; // set up for next time
+
+        if let Some((mut mods, total)) = self.cache.get(&key) {
+            self.total_pages = total;
+            self.mods.append(&mut mods);
+            return;
+        }
+
+        self.loading = true;
+        self.error = None;
+        self.pending = Some(key.clone());
+        self.task = Some(activity.spawn(format!("Loading mods page {page}"), move |task| {
+            let mut on_retry = |n| task.set_label(format!("Loading mods page {page} (retry {n} of {})", net::RETRIES));
+            task.post(activity::catch_panic(|| fetch_page(&key, &mut on_retry)));
+        }));
+    }
+
+    /// Restarts the listing from page 1 with the current query. A page
+    /// still loading for the previous query is dropped.
+    fn refresh(&mut self, page_size: usize, activity: &Activity) {
+        self.started = true;
+        self.installed = None;
+        if let Some(stale) = self.task.take() {
+            activity.forget(stale);
+        }
+        self.pending = None;
+        self.loading = false;
+        self.retry = None;
+        self.prefetch = false;
+        self.paused = false;
+        self.mods.clear();
+        self.total_pages = 0;
+        self.start_fetch(1, page_size, activity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{pages::harness, testutil};
+
+    #[test]
+    fn mod_pages_resolve_by_id_or_alias() {
+        assert_eq!(parse_mod_url("https://mods.vintagestory.at/show/mod/1234"), Some(ModRef::Id(1234)));
+        assert_eq!(parse_mod_url("https://mods.vintagestory.at/show/mod/1234#tab-files"), Some(ModRef::Id(1234)));
+        assert_eq!(parse_mod_url("  mods.vintagestory.at/carryon "), Some(ModRef::Alias("carryon".into())));
+        assert_eq!(parse_mod_url("https://www.mods.vintagestory.at/primitive-survival?x=1"), Some(ModRef::Alias("primitive-survival".into())));
+    }
+
+    #[test]
+    fn release_file_names_cannot_leave_the_mods_folder() {
+        let release = |filename: &str| ApiRelease {
+            modversion: "1.2.0".into(),
+            filename: filename.into(),
+            ..serde_json::from_value(serde_json::json!({})).unwrap()
+        };
+        assert_eq!(release_file(&release("carryon_1.2.0.zip"), "carryon"), "carryon_1.2.0.zip");
+        assert_eq!(release_file(&release("sub/carryon.zip"), "carryon"), "carryon.zip");
+        for bad in ["", "  ", "..", "../../.bashrc", "/etc/passwd", "\\evil.zip", "..\\..\\evil.zip", "a..zip"] {
+            assert_eq!(release_file(&release(bad), "carryon"), "carryon_1.2.0.zip", "{bad:?}");
+        }
+        // the fallback is built from API data as well
+        let hostile = ApiRelease { modversion: "/../../x".into(), ..release("") };
+        assert_eq!(release_file(&hostile, "a/b"), "a_b______x.zip");
+    }
+
+    #[test]
+    fn installs_run_side_by_side_and_cancel_alone() {
+        let mut page = ModsPage::default();
+        let activity = Activity::default();
+        let mut workers = Vec::new();
+        for modid in ["carryon", "smithingplus"] {
+            let guard = activity.start(modid);
+            let install = Install { file: format!("{modid}.zip"), inst: 1, task: guard.task(), cancel: Arc::default(), progress: (0, None) };
+            page.installs.insert(modid.to_string(), install);
+            workers.push(guard);
+        }
+        let transfer = ProgressEvent::Transfer { done: 10, total: Some(40), elapsed: Duration::ZERO };
+        workers[1].post(InstallEvent::Progress(transfer));
+        page.installs["carryon"].cancel.store(true, AtomicOrdering::Relaxed);
+        workers[0].post(InstallEvent::Done(Err("cancelled".into())));
+
+        let notify = Notifier::default();
+        page.poll_install(&activity, &notify);
+        assert!(!page.installs.contains_key("carryon"));
+        assert_eq!(page.installs["smithingplus"].progress, (10, Some(40)));
+    }
+
+    #[test]
+    fn badges_pick_the_newest_supported_install() {
+        let m: ApiMod = serde_json::from_value(serde_json::json!({
+            "modid": 7, "name": "Carry On", "gameversions": ["v1.19.8", "v1.20.0"],
+        }))
+        .unwrap();
+        let installed = ["1.20.1".to_string(), "1.20.0".into(), "1.19.8".into()];
+        assert_eq!(supported_installed(&m, &installed), Some("1.20.0"));
+        assert_eq!(supported_installed(&m, &installed[..1]), None);
+        // older listings without the field still parse
+        let bare: ApiMod = serde_json::from_value(serde_json::json!({"modid": 8})).unwrap();
+        assert!(bare.game_versions.is_empty());
+    }
+
+    #[test]
+    fn installs_report_progress_and_drop_non_mods() {
+        let url = testutil::serve_once("200 OK", 9, b"not a zip");
+        let tmp = tempfile::tempdir().unwrap();
+        let inst = testutil::instance(tmp.path());
+
+        let mut seen = Vec::new();
+        let res = install_file(&inst, &url, "notamod.zip", &AtomicBool::new(false), &mut |ev| {
+            if let ProgressEvent::Transfer { done, total, .. } = ev {
+                seen.push((done, total));
+            }
+        });
+        assert_eq!(res.unwrap_err(), "notamod.zip is not a Vintage Story mod");
+        assert_eq!(seen.last(), Some(&(9, Some(9))));
+        assert!(!inst.mods_dir().join("notamod.zip").exists());
+    }
+
+    #[test]
+    fn logo_fetches_are_capped() {
+        // every fetch fails at once, without touching the network
+        let mut thumbs = Thumbnails { fetch: |_| None, ..Thumbnails::default() };
+        let started: Vec<bool> = (0..THUMB_FETCHES as u32 + 2).map(|id| thumbs.want(id, "logo.png")).collect();
+        assert_eq!(started.iter().filter(|s| **s).count(), THUMB_FETCHES);
+        assert!(!thumbs.want(0, "logo.png"), "already in flight");
+        assert!(!thumbs.want(99, ""), "no logo to fetch");
+
+        // finished fetches free their slots; a failed logo isn't asked for again
+        let ctx = harness::context();
+        while !thumbs.loading.is_empty() {
+            thumbs.poll(&ctx);
+        }
+        assert!(!thumbs.want(0, "logo.png"));
+        assert!(thumbs.want(THUMB_FETCHES as u32, "logo.png"));
+    }
+
+    #[test]
+    fn sorts_map_to_the_api_order() {
+        assert_eq!(order_by("downloads"), "downloads");
+        assert_eq!(order_by("latest"), "asset.created");
+        assert_eq!(order_by("bogus"), "asset.created");
+    }
+
+    #[test]
+    fn search_waits_for_typing_to_pause() {
+        let mut page = ModsPage::default();
+        let t0 = Instant::now();
+        page.typed(t0);
+        page.typed(t0 + Duration::from_millis(300));
+        assert!(!page.search_due(t0 + SEARCH_DEBOUNCE));
+        assert!(page.search_due(t0 + Duration::from_millis(300) + SEARCH_DEBOUNCE));
+        assert!(!page.search_due(t0 + Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn releases_are_matched_to_the_game_version() {
+        let release = |ver: &str, tags: &[&str]| ApiRelease {
+            modidstr: "carryon".into(),
+            modversion: ver.into(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            created: String::new(),
+            mainfile: String::new(),
+            filename: String::new(),
+        };
+        let releases = [release("1.9.0", &["v1.20.0"]), release("1.8.0", &["v1.19.8", "v1.20.0"]), release("1.7.0", &["v1.19.8"])];
+        assert_eq!(newest_compatible(&releases, "1.19.8").map(|r| r.modversion.as_str()), Some("1.8.0"));
+        assert_eq!(newest_compatible(&releases, "1.20.0").map(|r| r.modversion.as_str()), Some("1.9.0"));
+        assert!(newest_compatible(&releases, "1.18.0").is_none());
+        assert_eq!(release_file(&releases[2], "carryon"), "carryon_1.7.0.zip");
+    }
+
+    #[test]
+    fn download_links_become_files() {
+        assert_eq!(
+            parse_mod_url("https://mods.vintagestory.at/download/9876/carryon_1.8.0.zip"),
+            Some(ModRef::File {
+                url: "https://mods.vintagestory.at/download/9876/carryon_1.8.0.zip".into(),
+                file: "carryon_1.8.0.zip".into(),
+            })
+        );
+        assert_eq!(
+            parse_mod_url("https://mods.vintagestory.at/download?fileid=9876"),
+            Some(ModRef::File { url: "https://mods.vintagestory.at/download?fileid=9876".into(), file: "mod_9876.zip".into() })
+        );
+        assert_eq!(
+            parse_mod_url("https://example.com/mods/MyMod.ZIP"),
+            Some(ModRef::File { url: "https://example.com/mods/MyMod.ZIP".into(), file: "MyMod.ZIP".into() })
+        );
+    }
+
+    #[test]
+    fn other_links_are_rejected() {
+        assert_eq!(parse_mod_url("https://mods.vintagestory.at/list/mod"), None);
+        assert_eq!(parse_mod_url("https://mods.vintagestory.at/home"), None);
+        assert_eq!(parse_mod_url("https://mods.vintagestory.at/show/mod/abc"), None);
+        assert_eq!(parse_mod_url("https://example.com/page"), None);
+        assert_eq!(parse_mod_url("ftp://example.com/a.zip"), None);
+        assert_eq!(parse_mod_url(""), None);
     }
 }
@@ -0,0 +1,488 @@
+//! pages/settings.rs – launcher footprint and housekeeping
+use std::{fs, io};
+
+use eframe::egui::{self, CentralPanel};
+
+use crate::{
+    activity::{self, Activity, TaskId, TryRecvError},
+    config::{Config, OnExit, Theme, DEFAULT_CDN},
+    disk,
+    net,
+    notify::Notifier,
+    pages::{
+        instances::InstancesPage,
+        versions::{self, VersionPage},
+    },
+    paths,
+};
+
+/// Disk usage snapshot, measured on a worker.
+#[derive(Clone, Copy, Default)]
+struct Usage {
+    versions:       u64, // whole versions dir, archives included
+    archives:       u64, // kept vs_archive.tar.gz files
+    instances:      u64,
+    instance_count: usize,
+    cache:          u64,
+    backups:        u64,
+}
+
+/// Work the Settings page hands back to the app.
+pub enum SettingsCmd {
+    /// fetch and extract this version again (owned by the Versions page)
+    Redownload(String),
+    None,
+}
+
+#[derive(Default)]
+pub struct SettingsPage {
+    usage:      Option<Usage>,
+    usage_task: Option<TaskId>,
+    /* "verify all installs": (version, problem) for each broken one */
+    broken:     Option<Vec<(String, String)>>,
+    verify_task: Option<TaskId>,
+    deleting:   Vec<(String, TaskId)>,
+    /* mirror speed test */
+    speed:      Option<Result<net::Speed, String>>,
+    speed_task: Option<TaskId>,
+    /// settings changed since the last save; written once no field is mid-edit
+    unsaved:    bool,
+}
+
+impl SettingsPage {
+    pub fn ui(
+        &mut self,
+        ctx: &egui::Context,
+        activity: &Activity,
+        notify: &Notifier,
+        config: &mut Config,
+    ) -> SettingsCmd {
+        let mut cmd = SettingsCmd::None;
+        self.poll_usage(ctx, activity);
+        self.poll_verify(activity, notify);
+        if self.usage.is_none() && self.usage_task.is_none() {
+            self.measure(activity);
+        }
+
+        CentralPanel::default().show(ctx, |ui| {
+            ui.heading("Settings");
+            ui.separator();
+
+            let before = config.clone();
+            let mut busy = false;
+            ui.strong("Mods");
+            ui.horizontal(|ui| {
+                ui.label("Mods per page:");
+                busy |= editing(&ui.add(egui::DragValue::new(&mut config.mods_page_size).clamp_range(12..=500)));
+            });
+            ui.checkbox(&mut config.mods_auto_load, "Load the mod list when the Mods page opens")
+                .on_hover_text("Turn off on metered connections; the page then waits for a search or Load mods");
+            ui.checkbox(&mut config.block_missing_deps, "Don't launch instances with unmet mod dependencies")
+                .on_hover_text("Off: the instance card only warns");
+            ui.separator();
+            busy |= self.mirror_ui(ui, activity, config);
+            ui.separator();
+            busy |= Self::data_dir_ui(ui, config);
+            ui.separator();
+            busy |= Self::instances_root_ui(ui, config);
+            ui.horizontal(|ui| {
+                ui.label("Theme:");
+                ui.selectable_value(&mut config.theme, Theme::System, "System");
+                ui.selectable_value(&mut config.theme, Theme::Light, "Light");
+                ui.selectable_value(&mut config.theme, Theme::Dark, "Dark");
+            });
+            ui.horizontal(|ui| {
+                ui.label("When the launcher closes while games run:");
+                ui.selectable_value(&mut config.on_exit, OnExit::Leave, "Leave them running");
+                ui.selectable_value(&mut config.on_exit, OnExit::Stop, "Stop them");
+                ui.selectable_value(&mut config.on_exit, OnExit::Ask, "Ask");
+            });
+            self.unsaved |= *config != before;
+            if self.unsaved && !busy {
+                self.unsaved = false;
+                if let Err(e) = config.save() {
+                    notify.error(format!("Could not save settings: {e}"));
+                }
+            }
+            ui.separator();
+
+            self.usage_ui(ui, activity, notify);
+            ui.separator();
+            self.verify_ui(ui, activity, &mut cmd);
+        });
+        cmd
+    }
+
+    /// Where versions, instances and caches live; applied on the next start.
+    /// Returns whether the field is still being edited.
+    fn data_dir_ui(ui: &mut egui::Ui, config: &mut Config) -> bool {
+        ui.strong("Data folder");
+        let default = paths::default_base_dir();
+        let mut busy = false;
+        ui.horizontal(|ui| {
+            ui.label("Versions, instances and caches in:");
+            let field = ui.add(
+                egui::TextEdit::singleline(&mut config.data_dir)
+                    .hint_text(default.to_string_lossy())
+                    .desired_width(320.0),
+            );
+            busy = editing(&field);
+            field.on_hover_text("e.g. a folder on a bigger drive; empty = the default. settings.json stays in the default folder");
+            if !config.data_dir.is_empty() && ui.small_button("Default").clicked() {
+                config.data_dir.clear();
+            }
+        });
+        if !config.data_dir.trim().is_empty() && config.data_dir().is_none() {
+            ui.colored_label(ui.visuals().warn_fg_color, "Use an absolute path; until then the default is used");
+        }
+        let wanted = config.data_dir().unwrap_or_else(|| default.clone());
+        if wanted != paths::base_dir() {
+            ui.colored_label(ui.visuals().warn_fg_color, "Restart the launcher to switch folders");
+            let current = paths::base_dir();
+            if paths::holds_data(&current) && !paths::holds_data(&wanted) {
+                ui.label(
+                    egui::RichText::new(format!(
+                        "Existing versions and instances are not moved. Close the launcher and move the contents of {} to {} to keep them.",
+                        current.display(),
+                        wanted.display()
+                    ))
+                    .small(),
+                );
+            }
+        }
+        busy
+    }
+
+    /// Parent folder for the data of instances created from now on.
+    /// Returns whether the field is still being edited.
+    fn instances_root_ui(ui: &mut egui::Ui, config: &mut Config) -> bool {
+        ui.strong("Instances");
+        let mut busy = false;
+        ui.horizontal(|ui| {
+            ui.label("New instance data in:");
+            let default = paths::instances_dir().to_string_lossy().into_owned();
+            let field =
+                ui.add(egui::TextEdit::singleline(&mut config.instances_root).hint_text(default).desired_width(320.0));
+            busy = editing(&field);
+            field.on_hover_text("e.g. a folder on another drive; empty = the launcher's own instances folder");
+            if !config.instances_root.is_empty() && ui.small_button("Default").clicked() {
+                config.instances_root.clear();
+            }
+        });
+        if !config.instances_root.trim().is_empty() && config.instances_root().is_none() {
+            ui.colored_label(ui.visuals().warn_fg_color, "Use an absolute path; until then the default is used");
+        }
+        ui.label(egui::RichText::new("Existing instances keep their data where it is.").small().weak());
+        busy
+    }
+
+    /// Download mirror: base URL override plus a ranged-download speed test.
+    /// Returns whether one of its fields is still being edited.
+    fn mirror_ui(&mut self, ui: &mut egui::Ui, activity: &Activity, config: &mut Config) -> bool {
+        if let Some(task) = self.speed_task {
+            match activity.try_take(task) {
+                Ok(res) => {
+                    self.speed = Some(res);
+                    self.speed_task = None;
+                }
+                Err(TryRecvError::Disconnected) => self.speed_task = None,
+                Err(TryRecvError::Empty) => {}
+            }
+        }
+        ui.strong("Downloads");
+        let mut busy = false;
+        ui.horizontal(|ui| {
+            ui.label("Mirror:");
+            let field = ui.add(egui::TextEdit::singleline(&mut config.cdn_mirror).hint_text(DEFAULT_CDN).desired_width(320.0));
+            busy |= editing(&field);
+            field.on_hover_text("Base URL laid out like the official gamefiles/ folder; empty = official CDN");
+            if !config.cdn_mirror.is_empty() && ui.small_button("Default").clicked() {
+                config.cdn_mirror.clear();
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Speed limit:");
+            let limit = ui.add(
+                egui::DragValue::new(&mut config.download_limit_kbps)
+                    .clamp_range(0..=1_000_000)
+                    .suffix(" KB/s"),
+            );
+            busy |= editing(&limit);
+            limit.on_hover_text("Caps all downloads together; 0 = unlimited. Applies to downloads started from now on");
+            if config.download_limit_kbps == 0 {
+                ui.weak("unlimited");
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Versions at once:");
+            let slots = ui.add(egui::DragValue::new(&mut config.parallel_downloads).clamp_range(1..=8));
+            busy |= editing(&slots);
+            slots.on_hover_text("Game versions downloading in parallel; the rest wait in a queue");
+        });
+        ui.horizontal(|ui| {
+            let testing = self.speed_task.is_some();
+            if ui.add_enabled(!testing, egui::Button::new("Test mirror speed")).clicked() {
+                self.test_speed(ui.ctx(), activity, config.cdn_base());
+            }
+            if testing {
+                ui.spinner();
+            }
+            match &self.speed {
+                Some(Ok(s)) => {
+                    ui.label(format!(
+                        "{} ms to first byte, {}/s",
+                        s.latency.as_millis(),
+                        disk::human_bytes(s.throughput as u64)
+                    ));
+                }
+                Some(Err(e)) => {
+                    ui.colored_label(ui.visuals().warn_fg_color, e);
+                }
+                None => {}
+            }
+        });
+        busy
+    }
+
+    fn test_speed(&mut self, ctx: &egui::Context, activity: &Activity, base: String) {
+        const SAMPLE: u64 = 1 << 20;
+        self.speed = None;
+        let ctx = ctx.clone();
+        self.speed_task = Some(activity.spawn("Testing mirror speed", move |task| {
+            let res: Result<net::Speed, String> = match sample_version() {
+                Some(ver) => activity::catch_panic(|| net::speed_test(&versions::client_url(&base, &ver), SAMPLE)),
+                None => Err("Fetch the version list first; the test downloads part of a client".into()),
+            };
+            task.post(res);
+            ctx.request_repaint();
+        }));
+    }
+
+    fn usage_ui(&mut self, ui: &mut egui::Ui, activity: &Activity, notify: &Notifier) {
+        ui.horizontal(|ui| {
+            ui.strong("Disk usage");
+            if self.usage_task.is_some() {
+                ui.spinner();
+            } else if ui.small_button("↻").on_hover_text("Measure again").clicked() {
+                self.measure(activity);
+            }
+        });
+        let Some(u) = self.usage else {
+            ui.label("Measuring…");
+            return;
+        };
+        let mut clear_archives = false;
+        let mut clear_cache = false;
+        egui::Grid::new("usage_grid").num_columns(3).striped(true).show(ui, |ui| {
+            ui.label("Game versions");
+            ui.label(disk::human_bytes(u.versions));
+            ui.end_row();
+
+            ui.label("  of which kept archives");
+            ui.label(disk::human_bytes(u.archives));
+            clear_archives = ui
+                .add_enabled(u.archives > 0, egui::Button::new("Clear archives"))
+                .on_hover_text("Installed versions keep working; re-downloading fetches them again")
+                .clicked();
+            ui.end_row();
+
+            ui.label(format!("Instances ({})", u.instance_count));
+            ui.label(disk::human_bytes(u.instances));
+            ui.end_row();
+
+            ui.label("Cache");
+            ui.label(disk::human_bytes(u.cache));
+            clear_cache = ui.add_enabled(u.cache > 0, egui::Button::new("Clear cache")).clicked();
+            ui.end_row();
+
+            ui.label("Backups");
+            ui.label(disk::human_bytes(u.backups));
+            ui.end_row();
+        });
+
+        if clear_archives {
+            match remove_archives() {
+                Ok(n) => notify.success(format!("Removed {n} archive(s)")),
+                Err(e) => notify.error(format!("Clear error: {e}")),
+            }
+            self.measure(activity);
+        }
+        if clear_cache {
+            match fs::remove_dir_all(paths::cache_dir()) {
+                Ok(()) => notify.success("Cache cleared"),
+                Err(e) => notify.error(format!("Clear error: {e}")),
+            }
+            self.measure(activity);
+        }
+    }
+
+    /// "Verify all installs": checks every version folder on a worker and
+    /// lists the broken ones with Re-download / Delete.
+    fn verify_ui(&mut self, ui: &mut egui::Ui, activity: &Activity, cmd: &mut SettingsCmd) {
+        ui.horizontal(|ui| {
+            ui.strong("Installed versions");
+            let running = self.verify_task.is_some();
+            if ui.add_enabled(!running, egui::Button::new("Verify all installs")).clicked() {
+                self.verify(ui.ctx(), activity);
+            }
+            if running {
+                ui.spinner();
+            }
+        });
+        let Some(broken) = &self.broken else { return };
+        if broken.is_empty() {
+            ui.label("All installs look fine");
+            return;
+        }
+        let mut delete = None;
+        egui::Grid::new("broken_grid").num_columns(3).striped(true).show(ui, |ui| {
+            for (ver, problem) in broken {
+                ui.label(format!("v{ver}"));
+                ui.colored_label(ui.visuals().warn_fg_color, problem);
+                ui.horizontal(|ui| {
+                    if self.deleting.iter().any(|(v, _)| v == ver) {
+                        ui.spinner();
+                        ui.label("Deleting…");
+                        return;
+                    }
+                    if ui.button("⬇ Re-download").clicked() {
+                        *cmd = SettingsCmd::Redownload(ver.clone());
+                    }
+                    if ui.button("🗑 Delete").clicked() {
+                        delete = Some(ver.clone());
+                    }
+                });
+                ui.end_row();
+            }
+        });
+        if let Some(ver) = delete {
+            let task = disk::remove_dir_in_background(paths::versions_dir().join(&ver), activity);
+            self.deleting.push((ver, task));
+        }
+    }
+
+    fn verify(&mut self, ctx: &egui::Context, activity: &Activity) {
+        let ctx = ctx.clone();
+        self.verify_task = Some(activity.spawn("Verifying installs", move |task| {
+            let broken: Vec<(String, String)> = version_names()
+                .filter_map(|ver| versions::verify_install(&ver).err().map(|e| (ver, e)))
+                .collect();
+            task.post(broken);
+            ctx.request_repaint();
+        }));
+    }
+
+    fn poll_verify(&mut self, activity: &Activity, notify: &Notifier) {
+        if let Some(task) = self.verify_task {
+            match activity.try_take::<Vec<(String, String)>>(task) {
+                Ok(mut broken) => {
+                    broken.sort();
+                    self.broken = Some(broken);
+                    self.verify_task = None;
+                }
+                Err(TryRecvError::Disconnected) => self.verify_task = None,
+                Err(TryRecvError::Empty) => {}
+            }
+        }
+        let mut done = Vec::new();
+        self.deleting.retain(|(ver, task)| match activity.try_take::<io::Result<()>>(*task) {
+            Ok(res) => {
+                done.push((ver.clone(), res));
+                false
+            }
+            Err(TryRecvError::Empty) => true,
+            Err(TryRecvError::Disconnected) => false,
+        });
+        for (ver, res) in done {
+            match res {
+                Ok(()) => {
+                    if let Some(b) = &mut self.broken { b.retain(|(v, _)| *v != ver); }
+                    notify.success(format!("Deleted v{ver}"));
+                    self.usage = None;
+                }
+                Err(e) => notify.error(format!("Delete error: {e}")),
+            }
+        }
+    }
+
+    /// Starts a fresh measurement; the old numbers stay up until it lands.
+    fn measure(&mut self, activity: &Activity) {
+        self.usage_task = Some(activity.spawn("Measuring disk usage", |task| {
+            let instances = InstancesPage::load_instances();
+            task.post(Usage {
+                versions: disk::dir_size(&paths::versions_dir()),
+                archives: version_names().map(|v| disk::dir_size(&paths::archive_path(&v))).sum(),
+                instances: disk::dir_size(&paths::instances_dir())
+                    + instances
+                        .iter()
+                        .filter(|i| i.data_root.is_some() && i.external_data.is_none())
+                        .map(|i| disk::dir_size(&i.data_dir()))
+                        .sum::<u64>(),
+                instance_count: instances.len(),
+                cache: disk::dir_size(&paths::cache_dir()),
+                backups: disk::dir_size(&paths::backups_dir()),
+            });
+        }));
+    }
+
+    fn poll_usage(&mut self, ctx: &egui::Context, activity: &Activity) {
+        let Some(task) = self.usage_task else { return };
+        match activity.try_take(task) {
+            Ok(u) => {
+                self.usage = Some(u);
+                self.usage_task = None;
+            }
+            Err(TryRecvError::Empty) => {
+                ctx.request_repaint_after(std::time::Duration::from_millis(200));
+            }
+            Err(TryRecvError::Disconnected) => self.usage_task = None,
+        }
+    }
+}
+
+/// Whether `field` (a text field or drag value) is still being edited: the
+/// settings are saved when it loses focus or is let go, not on every
+/// keystroke or drag step.
+fn editing(field: &egui::Response) -> bool {
+    field.has_focus() || field.dragged()
+}
+
+/// A version the CDN should have: the newest one on disk, else the newest
+/// in the cached API list.
+fn sample_version() -> Option<String> {
+    let mut vers: Vec<String> = version_names().collect();
+    if vers.is_empty() {
+        let cached: serde_json::Value = fs::read_to_string(paths::cache_dir().join("gameversions.json"))
+            .ok()
+            .and_then(|txt| serde_json::from_str(&txt).ok())?;
+        vers = cached
+            .as_array()?
+            .iter()
+            .filter(|v| v["kind"] == "stable")
+            .filter_map(|v| v["ver"].as_str().map(str::to_owned))
+            .collect();
+    }
+    vers.into_iter().max_by(|a, b| VersionPage::version_cmp(a, b))
+}
+
+/// Folder names under the versions dir, one per downloaded version.
+fn version_names() -> impl Iterator<Item = String> {
+    fs::read_dir(paths::versions_dir())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|e| e.file_name().into_string().ok())
+}
+
+/// Deletes every kept download archive; returns how many were removed.
+fn remove_archives() -> io::Result<usize> {
+    let mut n = 0;
+    for ver in version_names() {
+        match fs::remove_file(paths::archive_path(&ver)) {
+            Ok(()) => n += 1,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(n)
+}
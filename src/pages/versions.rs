@@ -1,55 +1,160 @@
 //! src/pages/versions.rs – v0.4.1 with semver sorting & toggle
 
 use std::{
+    cmp::Ordering,
+    collections::VecDeque,
     fs,
-    io::{self, Read, Write},
-    path::PathBuf,
+    io,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering as AtomicOrdering},
+        Arc,
+    },
     thread,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
-use compress_tools::{uncompress_archive, Ownership};
-use crossbeam_channel::{unbounded, Receiver};
-use dirs::data_local_dir;
 use eframe::egui::{self, CentralPanel, ProgressBar};
+use md5::Md5;
 use open;
-use reqwest::blocking::Client;
 use semver::Version;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    activity::{self, Activity, TaskId, TryRecvError},
+    config::Config,
+    disk, extract, launch,
+    net::{self, Connectivity, ProgressEvent},
+    notify::{Action, Notifier},
+    paths,
+    pages::{fuzzy_filter, instances::InstancesPage, list_keys, ListKey},
+};
 
 /*────────── version record ─────────*/
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 struct VersionInfo {
     ver:  String, // "1.20.11" or "1.21-rc.2"
     kind: String, // "stable" | "rc" | "preview" | "dev"
-}
-
-/*────────── background events ──────*/
-enum ProgressEvent {
-    Progress(f32), // 0.0‒1.0
-    Error(String),
-    Finished,
+    /// flagged newest by the API, else the newest stable we know of
+    #[serde(default)]
+    latest: bool,
+    #[serde(default)]
+    recommended: bool,
 }
 
 /*────────── task state ─────────────*/
-enum TaskState {
-    None,
-    InProgress { ver: String, rx: Receiver<ProgressEvent> },
-    Done,
+/// One running download and what its progress row shows.
+struct Download {
+    ver:       String,
+    task:      TaskId,
+    cancel:    Arc<AtomicBool>,
+    /// 0.0‒1.0
+    frac:      f32,
+    /// set instead of a fraction while the download size is unknown
+    bytes:     Option<u64>,
+    /// speed of this download
+    rate:      net::RateMeter,
+    /// bytes still to come, when the size is known
+    remaining: Option<u64>,
+    /// retry under way after a failed connection
+    retrying:  Option<u32>,
+    /// nothing to check the archive against
+    unverified: bool,
 }
-impl Default for TaskState {
-    fn default() -> Self {
-        TaskState::None
+
+impl Download {
+    fn new(ver: String, task: TaskId, cancel: Arc<AtomicBool>) -> Self {
+        Self {
+            ver,
+            task,
+            cancel,
+            frac: 0.0,
+            bytes: None,
+            rate: net::RateMeter::default(),
+            remaining: None,
+            retrying: None,
+            unverified: false,
+        }
     }
+
+    /// Applies every event the worker posted. `Some` with the final event
+    /// once the worker is done, which also ends the draining.
+    fn drain(&mut self, activity: &Activity) -> Option<ProgressEvent> {
+        loop {
+            let evt = match activity.try_take(self.task) {
+                Ok(evt) => evt,
+                Err(TryRecvError::Empty) => return None,
+                // worker gone without a final event: treat as a failure
+                Err(TryRecvError::Disconnected) => {
+                    ProgressEvent::Error("download worker stopped unexpectedly".into())
+                }
+            };
+            match evt {
+                ProgressEvent::Progress(f) => {
+                    self.frac = f;
+                    self.bytes = None;
+                }
+                ProgressEvent::Bytes(got) => self.bytes = Some(got),
+                ProgressEvent::Transfer { done, total, elapsed } => {
+                    self.rate.push(elapsed, done);
+                    self.remaining = total.map(|t| t.saturating_sub(done));
+                    self.retrying = None;
+                }
+                ProgressEvent::Retrying(n) => self.retrying = Some(n),
+                ProgressEvent::Unverified => self.unverified = true,
+                last => return Some(last),
+            }
+        }
+    }
+}
+
+/// Build metadata from the launcher release feed; any field may be absent.
+#[derive(Default)]
+struct ReleaseMeta {
+    filesize: Option<u64>,
+    md5:      Option<String>,
+    sha256:   Option<String>,
+    date:     Option<String>,
+}
+
+/// Everything the details window shows for one build.
+struct Details {
+    info:    VersionInfo,
+    url:     String,
+    /// `verify_install` result, taken when the window opened
+    install: Result<(), String>,
+    /// bytes of the kept archive, if any
+    archive: Option<u64>,
+    meta:    Option<Result<ReleaseMeta, String>>,
+    meta_task: Option<TaskId>,
+}
+
+/// Where a version stands in the download pipeline.
+#[derive(PartialEq)]
+enum Slot {
+    Idle,
+    Downloading,
+    Queued,
+}
+
+/// A download as seen from another page.
+pub enum Fetch {
+    Queued,
+    /// fraction done; `None` while the size is unknown
+    Running(Option<f32>),
 }
 
 /*────────── UI state ───────────────*/
 #[derive(Default)]
 pub struct VersionPage {
-    pub versions:      Vec<VersionInfo>,
-    status_msg:    Option<String>,
-    progress_frac: Option<f32>,
-    task:          TaskState,
+    versions:      Vec<VersionInfo>,
+    /// running downloads, oldest first; at most `Config::parallel_downloads`
+    downloads:     Vec<Download>,
+    /// versions waiting for a download slot
+    queue:         VecDeque<String>,
+    list_task:     Option<TaskId>,
 
     /* ui controls */
     filter_text:    String,
@@ -57,22 +162,119 @@ pub struct VersionPage {
     sort_ascending: bool,
 
     loaded_once: bool,
+    /// the cached list was read for offline use (found or not)
+    cache_tried: bool,
+
+    /* free space on the versions volume, refreshed every few seconds */
+    free_bytes:   Option<u64>,
+    free_checked: Option<Instant>,
+
+    /// keyboard highlight, index into the filtered list
+    selected_row: Option<usize>,
+
+    /// archives left by an interrupted download (version, bytes so far);
+    /// `None` = rescan on the next frame
+    partials: Option<Vec<(String, u64)>>,
+
+    /// details window for the clicked row
+    details: Option<Details>,
+
+    /// installed version some instances still use, waiting for a confirmed delete
+    confirm_delete: Option<(String, Vec<String>)>,
+    /// version folders being removed
+    deleting: Vec<(String, TaskId)>,
 }
 
 /*────────── UI driver ─────────────*/
 impl VersionPage {
-    pub fn ui(&mut self, ctx: &egui::Context) {
-        self.poll_task(ctx);
+    /// Collects download and list results. The app calls this every frame,
+    /// whichever page is showing, so queued downloads keep moving.
+    pub fn poll(&mut self, ctx: &egui::Context, activity: &Activity, notify: &Notifier, config: &Config) {
+        self.poll_task(ctx, activity, notify, config);
+        self.poll_list(activity, notify);
+        self.poll_deletes(ctx, activity, notify);
+    }
+
+    /// Fetches the version list exactly once, deferred until we know we're
+    /// online; offline, the cached list stands in.
+    pub fn load_list(&mut self, activity: &Activity, net: Connectivity) {
+        if self.loaded_once {
+            return;
+        }
+        if net.is_online() {
+            self.fetch_versions(activity);
+            self.loaded_once = true;
+        } else if net.is_offline() && self.versions.is_empty() && !self.cache_tried {
+            self.load_cached_versions();
+            self.cache_tried = true;
+        }
+    }
+
+    /// Known versions that are not installed yet, newest first.
+    pub fn not_installed(&self) -> Vec<String> {
+        let mut out: Vec<String> = self
+            .versions
+            .iter()
+            .map(|v| v.ver.clone())
+            .filter(|v| !self.is_installed(v))
+            .collect();
+        out.sort_by(|a, b| Self::version_cmp(b, a));
+        out
+    }
+
+    /// Whether the version list is still being fetched.
+    pub fn list_loading(&self) -> bool {
+        self.list_task.is_some()
+    }
+
+    /// Whether a row is highlighted, so Enter is the list's to handle.
+    pub fn consumes_enter(&self) -> bool {
+        self.selected_row.is_some()
+    }
+
+    /// Where a download of `ver` stands, for pages waiting on it;
+    /// `None` once it has finished, failed or was never asked for.
+    pub fn fetch_state(&self, ver: &str) -> Option<Fetch> {
+        match self.slot(ver) {
+            Slot::Idle => None,
+            Slot::Queued => Some(Fetch::Queued),
+            Slot::Downloading => Some(Fetch::Running(
+                self.downloads.iter().find(|d| d.ver == ver).filter(|d| d.bytes.is_none()).map(|d| d.frac),
+            )),
+        }
+    }
+
+    pub fn ui(
+        &mut self,
+        ctx: &egui::Context,
+        activity: &Activity,
+        notify: &Notifier,
+        config: &Config,
+        net: Connectivity,
+        instances: &InstancesPage,
+    ) {
+        self.refresh_free_space(ctx);
 
         CentralPanel::default().show(ctx, |ui| {
-            /* auto-load exactly once */
-            if !self.loaded_once {
-                self.fetch_versions();
-                self.loaded_once = true;
-            }
-            if ui.button("Refresh").clicked() {
-                self.fetch_versions();
+            self.load_list(activity, net);
+            if net.is_offline() {
+                ui.colored_label(ui.visuals().warn_fg_color, "Offline — showing cached data");
             }
+            ui.horizontal(|ui| {
+                if ui.add_enabled(!net.is_offline(), egui::Button::new("Refresh")).clicked() {
+                    self.fetch_versions(activity);
+                    self.loaded_once = true;
+                }
+                if self.list_task.is_some() {
+                    ui.spinner();
+                    ui.label("Fetching list…");
+                }
+                if let Some(free) = self.free_bytes {
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.label(format!("{} free", disk::human_bytes(free)));
+                    });
+                }
+            });
 
             /* ── filter + sort bar ────────────────────────── */
             ui.horizontal(|ui| {
@@ -103,55 +305,403 @@ impl VersionPage {
                 }
             });
 
-            /* status + progress */
-            if let Some(msg) = &self.status_msg {
-                ui.label(msg);
-            }
-            if let Some(p) = self.progress_frac {
-                ui.add(ProgressBar::new(p).show_percentage());
+            /* progress, one row per running download */
+            for d in &self.downloads {
+                ui.horizontal(|ui| {
+                    if ui.button("Cancel").clicked() {
+                        d.cancel.store(true, AtomicOrdering::Relaxed);
+                    }
+                    ui.label(format!("Downloading v{}…", d.ver));
+                    if let Some(n) = d.retrying {
+                        ui.colored_label(ui.visuals().warn_fg_color, format!("connection lost, retry {n} of {}", net::RETRIES));
+                    }
+                    match d.bytes {
+                        Some(got) => {
+                            ui.spinner();
+                            ui.label(format!("{} so far (size unknown)", disk::human_bytes(got)));
+                        }
+                        None => {
+                            ui.add(ProgressBar::new(d.frac).show_percentage());
+                        }
+                    }
+                    if let Some(rate) = d.rate.per_second() {
+                        let mut text = format!("{}/s", disk::human_bytes(rate as u64));
+                        if let Some(eta) = d.remaining.and_then(|r| d.rate.eta(r)) {
+                            text.push_str(&format!(" · {} left", format_eta(eta)));
+                        }
+                        ui.label(egui::RichText::new(text).small());
+                    }
+                });
             }
 
             ui.separator();
 
             /* version list */
-            let mut to_download: Option<VersionInfo> = None;
+            let mut to_download: Option<String> = None;
+            let mut to_unqueue: Option<String> = None;
+            let mut to_detail: Option<VersionInfo> = None;
+            let mut to_delete: Option<String> = None;
+            self.partials_ui(ui, &mut to_download, notify);
+            let visible: Vec<&VersionInfo> =
+                self.visible_rows().into_iter().map(|i| &self.versions[i]).collect();
+            let key = list_keys(ctx, visible.len(), &mut self.selected_row);
+            if key == ListKey::Activate {
+                if let Some(v) = self.selected_row.map(|i| visible[i]) {
+                    if self.is_installed(&v.ver) {
+                        let _ = open::that(paths::install_dir(&v.ver));
+                    } else {
+                        to_download = Some(v.ver.clone());
+                    }
+                }
+            }
             egui::ScrollArea::vertical().show(ui, |ui| {
-                for v in self.versions.iter().filter(|v| self.matches_filter(v)) {
-                    ui.horizontal(|ui| {
-                        ui.label(format!("v{} ({})", v.ver, v.kind));
+                for (row, v) in visible.iter().enumerate() {
+                    let highlighted = self.selected_row == Some(row);
+                    let fill = if highlighted {
+                        ui.visuals().selection.bg_fill
+                    } else {
+                        egui::Color32::TRANSPARENT
+                    };
+                    let frame = egui::Frame::none().fill(fill).show(ui, |ui| ui.horizontal(|ui| {
+                        let installed = self.is_installed(&v.ver);
+                        let slot = self.slot(&v.ver);
+                        let deleting = self.deleting.iter().any(|(d, _)| *d == v.ver);
+                        let color = kind_color(&v.kind, ui.visuals());
+                        let label = ui.add(
+                            egui::Label::new(
+                                egui::RichText::new(format!("v{} ({})", v.ver, v.kind)).color(color),
+                            )
+                            .sense(egui::Sense::click()),
+                        );
+                        if label.clicked() {
+                            to_detail = Some((*v).clone());
+                        }
+                        if v.latest {
+                            ui.label(egui::RichText::new("★ latest").small().strong());
+                        }
+                        if v.recommended {
+                            ui.label(egui::RichText::new("recommended").small());
+                        }
+                        if installed {
+                            let users = instances.instances_using(&v.ver);
+                            if users.is_empty() {
+                                ui.label(egui::RichText::new("unused").small().weak())
+                                    .on_hover_text("No instance uses this version");
+                            } else {
+                                ui.label(egui::RichText::new(format!("used by {}", users.len())).small())
+                                    .on_hover_text(users.join("\n"));
+                            }
+                        }
+                        label
+                        .context_menu(|ui| {
+                            if installed {
+                                if ui.button("📂 Open dir").clicked() {
+                                    let _ = open::that(paths::install_dir(&v.ver));
+                                    ui.close_menu();
+                                }
+                                if slot == Slot::Idle && !deleting && ui.button("🗑 Delete").clicked() {
+                                    to_delete = Some(v.ver.clone());
+                                    ui.close_menu();
+                                }
+                            } else if slot == Slot::Idle && ui.button("⬇ Download").clicked() {
+                                to_download = Some(v.ver.clone());
+                                ui.close_menu();
+                            }
+                            let archive = paths::archive_path(&v.ver);
+                            if archive.exists() && ui.button("🗄 Reveal archive").clicked() {
+                                if let Err(e) = disk::reveal_file(&archive) {
+                                    notify.error(format!("Could not reveal archive: {e}"));
+                                }
+                                ui.close_menu();
+                            }
+                            if ui.button("📄 Changelog").clicked() {
+                                let _ = open::that(Self::changelog_url(&v.ver));
+                                ui.close_menu();
+                            }
+                        });
 
-                        if self.is_installed(&v.ver) {
-                            if ui.button("Open dir").clicked() {
-                                let _ = open::that(Self::install_dir(&v.ver));
+                        match slot {
+                            Slot::Downloading => {
+                                ui.spinner();
+                                ui.label("Downloading…");
+                            }
+                            Slot::Queued => {
+                                ui.label(egui::RichText::new("Queued").weak());
+                                if ui.small_button("✖").on_hover_text("Remove from queue").clicked() {
+                                    to_unqueue = Some(v.ver.clone());
+                                }
+                            }
+                            Slot::Idle if deleting => {
+                                ui.spinner();
+                                ui.label("Deleting…");
+                            }
+                            Slot::Idle if installed => {
+                                if ui.button("Open dir").clicked() {
+                                    let _ = open::that(paths::install_dir(&v.ver));
+                                }
+                                if ui.button("Delete").on_hover_text("Remove the install and its archive").clicked() {
+                                    to_delete = Some(v.ver.clone());
+                                }
+                            }
+                            Slot::Idle => {
+                                if ui.button("Download").clicked() {
+                                    to_download = Some(v.ver.clone());
+                                }
                             }
-                        } else if ui.button("Download").clicked() {
-                            to_download = Some(v.clone());
                         }
-                    });
+                    }));
+                    if highlighted && key == ListKey::Moved {
+                        frame.response.scroll_to_me(None);
+                    }
                 }
             });
 
             if let Some(v) = to_download {
-                self.spawn_download(v);
+                self.spawn_download(v, activity, config);
+            }
+            if let Some(v) = to_unqueue {
+                self.queue.retain(|q| *q != v);
+            }
+            if let Some(info) = to_detail {
+                self.open_details(info, activity, config);
+            }
+            if let Some(ver) = to_delete {
+                let users = instances.instances_using(&ver);
+                if users.is_empty() {
+                    self.delete(ver, activity);
+                } else {
+                    self.confirm_delete = Some((ver, users));
+                }
             }
         });
 
+        self.details_window(ctx, activity);
+        self.confirm_delete_dialog(ctx, activity);
         self.maybe_schedule_ticker(ctx);
     }
 
+    /// Removes `ver`'s folder (install and kept archive) in the background.
+    fn delete(&mut self, ver: String, activity: &Activity) {
+        let task = disk::remove_dir_in_background(paths::versions_dir().join(&ver), activity);
+        self.deleting.push((ver, task));
+    }
+
+    fn confirm_delete_dialog(&mut self, ctx: &egui::Context, activity: &Activity) {
+        let Some((ver, users)) = &self.confirm_delete else { return };
+        let (mut go, mut cancel) = (false, false);
+        egui::Window::new(format!("Delete v{ver}?"))
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.colored_label(ui.visuals().warn_fg_color, "⚠ These instances use this version:");
+                for name in users {
+                    ui.label(format!("• {name}"));
+                }
+                ui.label("They won't start until it is downloaded again.");
+                ui.horizontal(|ui| {
+                    go = ui.button("Delete anyway").clicked();
+                    cancel = ui.button("Cancel").clicked();
+                });
+            });
+        if go {
+            if let Some((ver, _)) = self.confirm_delete.take() {
+                self.delete(ver, activity);
+            }
+        }
+        if cancel {
+            self.confirm_delete = None;
+        }
+    }
+
+    fn poll_deletes(&mut self, ctx: &egui::Context, activity: &Activity, notify: &Notifier) {
+        let mut done = Vec::new();
+        self.deleting.retain(|(ver, task)| match activity.try_take::<io::Result<()>>(*task) {
+            Ok(res) => {
+                done.push((ver.clone(), res));
+                false
+            }
+            Err(TryRecvError::Empty) => true,
+            Err(TryRecvError::Disconnected) => false,
+        });
+        for (ver, res) in done {
+            match res {
+                Ok(()) => notify.success(format!("Deleted v{ver}")),
+                Err(e) => notify.error(format!("Could not delete v{ver}: {e}")),
+            }
+            self.partials = None;
+            self.free_checked = None;
+        }
+        if !self.deleting.is_empty() {
+            ctx.request_repaint_after(Duration::from_millis(200));
+        }
+    }
+
+    fn open_details(&mut self, info: VersionInfo, activity: &Activity, config: &Config) {
+        let (ver, kind) = (info.ver.clone(), info.kind.clone());
+        let task = activity.spawn(format!("Fetching v{ver} release info"), move |task| {
+            task.post(activity::catch_panic(|| fetch_release_meta(&kind, &ver)));
+        });
+        self.details = Some(Details {
+            url: client_url(&config.cdn_base(), &info.ver),
+            install: verify_install(&info.ver),
+            archive: fs::metadata(paths::archive_path(&info.ver)).ok().map(|m| m.len()),
+            meta: None,
+            meta_task: Some(task),
+            info,
+        });
+    }
+
+    fn details_window(&mut self, ctx: &egui::Context, activity: &Activity) {
+        let free = self.free_bytes;
+        let Some(d) = &mut self.details else { return };
+        if let Some(task) = d.meta_task {
+            match activity.try_take(task) {
+                Ok(meta) => {
+                    d.meta = Some(meta);
+                    d.meta_task = None;
+                }
+                Err(TryRecvError::Disconnected) => d.meta_task = None,
+                Err(TryRecvError::Empty) => ctx.request_repaint_after(Duration::from_millis(200)),
+            }
+        }
+        let mut open = true;
+        egui::Window::new(format!("v{}", d.info.ver))
+            .collapsible(false)
+            .resizable(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                let meta = d.meta.as_ref().and_then(|m| m.as_ref().ok());
+                let unknown = || if d.meta_task.is_some() { "…".to_string() } else { "unknown".to_string() };
+                egui::Grid::new("version_details").num_columns(2).striped(true).show(ui, |ui| {
+                    ui.label("Version");
+                    ui.label(&d.info.ver);
+                    ui.end_row();
+                    ui.label("Channel");
+                    let mut channel = d.info.kind.clone();
+                    if d.info.latest { channel.push_str(", latest"); }
+                    if d.info.recommended { channel.push_str(", recommended"); }
+                    ui.colored_label(kind_color(&d.info.kind, ui.visuals()), channel);
+                    ui.end_row();
+                    ui.label("Released");
+                    ui.label(meta.and_then(|m| m.date.clone()).unwrap_or_else(unknown));
+                    ui.end_row();
+                    ui.label("Download");
+                    ui.hyperlink_to(&d.url, &d.url);
+                    ui.end_row();
+                    ui.label("Archive size");
+                    ui.label(meta.and_then(|m| m.filesize).map(disk::human_bytes).unwrap_or_else(unknown));
+                    ui.end_row();
+                    if let Some(len) = meta.and_then(|m| m.filesize).filter(|_| d.install.is_err()) {
+                        ui.label("Disk space");
+                        let needed = space_needed(len).saturating_sub(d.archive.unwrap_or(0));
+                        let text = match free {
+                            Some(free) => format!("{} needed, {} free", disk::human_bytes(needed), disk::human_bytes(free)),
+                            None => format!("{} needed", disk::human_bytes(needed)),
+                        };
+                        match check_space(len, d.archive.unwrap_or(0), free) {
+                            Ok(()) => ui.label(text),
+                            Err(_) => ui.colored_label(ui.visuals().error_fg_color, format!("⚠ {text}"))
+                                .on_hover_text("The download will refuse to start until there is room"),
+                        };
+                        ui.end_row();
+                    }
+                    ui.label("MD5");
+                    match meta.and_then(|m| m.md5.as_deref()) {
+                        Some(md5) => ui.monospace(md5),
+                        None => ui.label(unknown()),
+                    };
+                    ui.end_row();
+                    if let Some(sha) = meta.and_then(|m| m.sha256.as_deref()) {
+                        ui.label("SHA-256");
+                        ui.monospace(sha);
+                        ui.end_row();
+                    }
+                    ui.label("Installed");
+                    match &d.install {
+                        Ok(()) => ui.label("✔ yes, verified"),
+                        Err(e) => ui.label(format!("no – {e}")),
+                    };
+                    ui.end_row();
+                    if let Some(len) = d.archive {
+                        ui.label("Kept archive");
+                        ui.label(disk::human_bytes(len));
+                        ui.end_row();
+                    }
+                });
+                if let Some(Err(e)) = &d.meta {
+                    ui.label(egui::RichText::new(format!("Release metadata unavailable: {e}")).small().weak());
+                }
+            });
+        if !open {
+            self.details = None;
+        }
+    }
+
+    /// "Unfinished downloads" box with Resume / Discard per leftover archive.
+    fn partials_ui(&mut self, ui: &mut egui::Ui, to_download: &mut Option<String>, notify: &Notifier) {
+        let downloading: Vec<String> = self.downloads.iter().map(|d| d.ver.clone()).collect();
+        let partials = self.partials.get_or_insert_with(|| {
+            fs::read_dir(paths::versions_dir())
+                .into_iter()
+                .flatten()
+                .flatten()
+                .filter_map(|e| e.file_name().into_string().ok())
+                .filter(|ver| !Self::install_exists(ver))
+                .filter_map(|ver| {
+                    let len = fs::metadata(paths::archive_path(&ver)).ok()?.len();
+                    Some((ver, len))
+                })
+                .collect()
+        });
+        let shown: Vec<&(String, u64)> =
+            partials.iter().filter(|(v, _)| !downloading.contains(v)).collect();
+        if shown.is_empty() {
+            return;
+        }
+        let mut discard = None;
+        ui.group(|ui| {
+            ui.label("Unfinished downloads");
+            for (ver, len) in shown {
+                ui.horizontal(|ui| {
+                    ui.label(format!("v{ver} – {} so far", disk::human_bytes(*len)));
+                    if ui.button("Resume").clicked() {
+                        *to_download = Some(ver.clone());
+                    }
+                    if ui.button("Discard").clicked() {
+                        discard = Some(ver.clone());
+                    }
+                });
+            }
+        });
+        if let Some(ver) = discard {
+            if let Err(e) = fs::remove_file(paths::archive_path(&ver)) {
+                notify.error(format!("Discard error: {e}"));
+            }
+            self.partials = None;
+        }
+    }
+
+    fn refresh_free_space(&mut self, ctx: &egui::Context) {
+        const EVERY: Duration = Duration::from_secs(5);
+        if self.free_checked.is_some_and(|t| t.elapsed() < EVERY) {
+            return;
+        }
+        self.free_bytes = disk::free_space(&paths::versions_dir());
+        self.free_checked = Some(Instant::now());
+        ctx.request_repaint_after(EVERY);
+    }
+
     /*────────── filter helper ───────*/
-    fn matches_filter(&self, v: &VersionInfo) -> bool {
-        let text_ok = self.filter_text.is_empty() || v.ver.contains(&self.filter_text);
-        let chan_ok = self.filter_channel.is_empty() || v.kind == self.filter_channel;
-        text_ok && chan_ok
+    /// Indices into `versions` to show: channel filter, then fuzzy text
+    /// match ranked by score.
+    fn visible_rows(&self) -> Vec<usize> {
+        filter_rows(&self.versions, &self.filter_text, &self.filter_channel)
     }
 
     /*────────── semver sort ─────────*/
     fn sort_versions(&mut self) {
         self.versions.sort_by(|a, b| {
-            let sa = Self::parse_semver(&a.ver);
-            let sb = Self::parse_semver(&b.ver);
-            let ord = sa.cmp(&sb); // None < Some(...)
+            let ord = Self::version_cmp(&a.ver, &b.ver);
             if self.sort_ascending {
                 ord
             } else {
@@ -160,188 +710,773 @@ impl VersionPage {
         });
     }
 
-    fn parse_semver(raw: &str) -> Option<Version> {
-        // Ensure "1.21" -> "1.21.0"
-        let parts: Vec<&str> = raw.split('-').collect();
-        let core = parts[0];
-        let mut nums: Vec<&str> = core.split('.').collect();
-        while nums.len() < 3 {
-            nums.push("0");
-        }
-        let mut fix = nums.join(".");
-        if parts.len() > 1 {
-            fix.push('-');
-            fix.push_str(parts[1]);
-        }
-        Version::parse(&fix).ok()
+    /// Semver ordering for raw game version strings; unparseable ones sort first.
+    pub(crate) fn version_cmp(a: &str, b: &str) -> Ordering {
+        let sa = parse_semver(a);
+        let sb = parse_semver(b);
+        sa.cmp(&sb).then_with(|| a.cmp(b)) // None < Some(...)
     }
 
     /*────────── paths / install check ───────*/
-    pub(crate) fn versions_dir() -> PathBuf {
-        data_local_dir()
-            .unwrap_or_else(|| PathBuf::from("~/.local/share"))
-            .join("vs_launcher/versions")
+    fn cache_file() -> PathBuf {
+        paths::cache_dir().join("gameversions.json")
     }
-    fn archive_path(ver: &str) -> PathBuf {
-        Self::versions_dir().join(ver).join("vs_archive.tar.gz")
-    }
-    pub(crate) fn install_dir(ver: &str) -> PathBuf {
-        Self::versions_dir().join(ver).join("install")
+    fn changelog_url(ver: &str) -> String {
+        format!("https://wiki.vintagestory.at/index.php?search=v{ver}")
     }
     fn is_installed(&self, ver: &str) -> bool {
-        let root = Self::install_dir(ver);
+        Self::install_exists(ver)
+    }
+    fn install_exists(ver: &str) -> bool {
+        let root = paths::install_dir(ver);
         root.join("vintagestory").exists()
             || root.join("vintagestory.exe").exists()
             || root.join("vintagestory/vintagestory").exists()
     }
 
     /*────────── fetch list from API ───────*/
-    fn fetch_versions(&mut self) {
-        self.status_msg = Some("Fetching list…".into());
+    fn fetch_versions(&mut self, activity: &Activity) {
         self.versions.clear();
 
-        let url = "https://mods.vintagestory.at/api/gameversions";
-        match Client::new().get(url).send().and_then(|r| r.json::<Value>()) {
-            Ok(json) => {
-                if let Some(arr) = json["gameversions"].as_array() {
-                    for obj in arr {
-                        let raw = obj["name"].as_str().unwrap_or("");
-                        let name = raw.trim_start_matches('v');
-                        let kind = obj["type"]
-                            .as_str()
-                            .map(|s| s.to_string())
-                            .unwrap_or_else(|| {
-                                if raw.contains("rc") {
-                                    "rc".into()
-                                } else if raw.contains("dev") {
-                                    "dev".into()
-                                } else if raw.contains("pre") {
-                                    "preview".into()
-                                } else {
-                                    "stable".into()
-                                }
-                            });
-                        self.versions.push(VersionInfo {
-                            ver: name.to_string(),
-                            kind,
-                        });
-                    }
-                    self.sort_versions();
-                    self.status_msg =
-                        Some(format!("Found {} versions", self.versions.len()));
-                } else {
-                    self.status_msg = Some("Unexpected JSON shape".into());
+        self.list_task = Some(activity.spawn("Fetching version list", |task| {
+            let mut on_retry = |n| task.set_label(format!("Fetching version list (retry {n} of {})", net::RETRIES));
+            task.post(activity::catch_panic(|| fetch_version_list(&mut on_retry)));
+        }));
+    }
+
+    fn poll_list(&mut self, activity: &Activity, notify: &Notifier) {
+        let Some(task) = self.list_task else { return };
+        let result: Result<Vec<VersionInfo>, String> = match activity.try_take(task) {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => Err("Version list worker stopped unexpectedly".into()),
+        };
+        self.list_task = None;
+        match result {
+            Ok(list) => {
+                self.versions = list;
+                self.sort_versions();
+                notify.success(format!("Found {} versions", self.versions.len()));
+                if let Ok(j) = serde_json::to_string(&self.versions) {
+                    let path = Self::cache_file();
+                    if let Some(p) = path.parent() { let _ = fs::create_dir_all(p); }
+                    let _ = fs::write(path, j);
                 }
             }
-            Err(e) => self.status_msg = Some(format!("Error: {e}")),
+            Err(e) => {
+                notify.error(format!("Could not fetch the version list: {e}"));
+                self.load_cached_versions();
+            }
+        }
+    }
+
+    /// Last list fetched from the API, for offline use.
+    fn load_cached_versions(&mut self) {
+        let cached: Option<Vec<VersionInfo>> = fs::read_to_string(Self::cache_file())
+            .ok()
+            .and_then(|txt| serde_json::from_str(&txt).ok());
+        if let Some(mut list) = cached {
+            list.retain(|v| safe_version_name(&v.ver));
+            self.versions = list;
+            self.sort_versions();
         }
     }
 
     /*────────── background thread mgmt ─────*/
-    fn spawn_download(&mut self, v: VersionInfo) {
-        if matches!(self.task, TaskState::InProgress { .. }) {
-            self.status_msg = Some("A download is already running".into());
-            return;
+    fn slot(&self, ver: &str) -> Slot {
+        if self.downloads.iter().any(|d| d.ver == ver) {
+            Slot::Downloading
+        } else if self.queue.iter().any(|q| q == ver) {
+            Slot::Queued
+        } else {
+            Slot::Idle
         }
-        let (tx, rx) = unbounded();
-        self.task = TaskState::InProgress {
-            ver: v.ver.clone(),
-            rx,
-        };
-        self.progress_frac = Some(0.0);
-        self.status_msg = Some(format!("Downloading v{}…", v.ver));
+    }
 
-        thread::spawn(move || {
-            if let Err(e) = download_and_extract(&v, &tx) {
-                let _ = tx.send(ProgressEvent::Error(e.to_string()));
-            }
-        });
+    /// Fetches `ver` again from scratch (dropping any kept archive) and
+    /// extracts it over the existing folder.
+    pub fn redownload(&mut self, ver: String, activity: &Activity, config: &Config) {
+        if self.slot(&ver) == Slot::Idle {
+            let _ = fs::remove_file(paths::archive_path(&ver));
+            self.partials = None;
+        }
+        self.spawn_download(ver, activity, config);
     }
 
-    fn poll_task(&mut self, ctx: &egui::Context) {
-        let mut next_state: Option<TaskState> = None;
+    /// Downloads `ver`, or queues it while every download slot is busy.
+    pub fn download(&mut self, ver: String, activity: &Activity, config: &Config) {
+        self.spawn_download(ver, activity, config);
+    }
 
-        if let TaskState::InProgress { ver, rx } = &mut self.task {
-            let ver_name = ver.clone();
-            let mut dirty = false;
+    /// Tries a failed download again, continuing from the archive it left.
+    pub fn retry_download(&mut self, ver: String, activity: &Activity, config: &Config) {
+        self.spawn_download(ver, activity, config);
+    }
 
-            while let Ok(evt) = rx.try_recv() {
+    /// Starts downloading `ver`, or queues it while `Config::parallel_downloads`
+    /// are already running. Asking again for a version that is already
+    /// running or queued does nothing.
+    fn spawn_download(&mut self, ver: String, activity: &Activity, config: &Config) {
+        if self.slot(&ver) != Slot::Idle {
+            return;
+        }
+        if self.downloads.len() >= config.parallel_downloads() {
+            self.queue.push_back(ver);
+            return;
+        }
+        let cancel = Arc::new(AtomicBool::new(false));
+        let guard = activity.start(format!("Downloading v{ver}"));
+        guard.set_cancel(cancel.clone());
+        self.downloads.push(Download::new(ver.clone(), guard.task(), cancel.clone()));
+
+        thread::spawn(move || {
+            let mut emit = |evt: ProgressEvent| {
                 match evt {
-                    ProgressEvent::Progress(f) => {
-                        self.progress_frac = Some(f);
-                        dirty = true;
-                    }
-                    ProgressEvent::Finished => {
-                        self.status_msg =
-                            Some(format!("v{ver_name} downloaded & extracted"));
-                        next_state = Some(TaskState::Done);
-                        self.progress_frac = None;
-                        dirty = true;
+                    ProgressEvent::Progress(f) => guard.set_progress(f),
+                    ProgressEvent::Bytes(got) => {
+                        guard.set_label(format!("Downloading v{ver} ({})", disk::human_bytes(got)))
                     }
-                    ProgressEvent::Error(e) => {
-                        self.status_msg = Some(format!("Error: {e}"));
-                        next_state = Some(TaskState::None);
-                        self.progress_frac = None;
-                        dirty = true;
+                    ProgressEvent::Retrying(n) => {
+                        guard.set_label(format!("Downloading v{ver} (retry {n} of {})", net::RETRIES))
                     }
+                    _ => {}
                 }
+                guard.post(evt);
+            };
+            match activity::catch_panic(|| download_and_extract(&ver, &mut emit, &cancel).map_err(|e| e.to_string())) {
+                Ok(()) => {}
+                Err(_) if cancel.load(AtomicOrdering::Relaxed) => guard.post(ProgressEvent::Cancelled),
+                Err(e) => guard.post(ProgressEvent::Error(e)),
             }
-            if dirty {
-                ctx.request_repaint();
+        });
+    }
+
+    /// Drains every running download; finished ones free their slot for
+    /// the next queued version.
+    fn poll_task(&mut self, ctx: &egui::Context, activity: &Activity, notify: &Notifier, config: &Config) {
+        let mut finished = Vec::new();
+        for (i, d) in self.downloads.iter_mut().enumerate() {
+            let Some(last) = d.drain(activity) else { continue };
+            let ver = &d.ver;
+            match last {
+                ProgressEvent::Finished if d.unverified => notify.warning(format!(
+                    "v{ver} downloaded & extracted, but not verified: no checksum is published for it"
+                )),
+                ProgressEvent::Finished => notify.success(format!("v{ver} downloaded & extracted")),
+                ProgressEvent::Cancelled => notify.success(format!("v{ver} download cancelled")),
+                ProgressEvent::Error(e) => notify.error_with(
+                    format!("v{ver} download failed: {e}"),
+                    Action::RetryDownload(ver.clone()),
+                ),
+                _ => {}
             }
+            finished.push(i);
+        }
+        if finished.is_empty() {
+            return;
         }
-        if let Some(s) = next_state {
-            self.task = s;
+        for i in finished.into_iter().rev() {
+            self.downloads.remove(i);
+        }
+        self.partials = None;
+        ctx.request_repaint();
+        while self.downloads.len() < config.parallel_downloads() {
+            let Some(ver) = self.queue.pop_front() else { break };
+            self.spawn_download(ver, activity, config);
         }
     }
 
     fn maybe_schedule_ticker(&self, ctx: &egui::Context) {
-        if matches!(self.task, TaskState::InProgress { .. }) {
+        if !self.downloads.is_empty() {
             ctx.request_repaint_after(Duration::from_millis(10));
         }
     }
 }
 
-/*────────── worker thread ──────────*/
-fn download_and_extract(
-    v: &VersionInfo,
-    tx: &crossbeam_channel::Sender<ProgressEvent>,
+/*────────── filtering / parsing ────*/
+/// Channel filter: empty matches everything, otherwise the kind must match
+/// (case-insensitively – the API has sent both "Stable" and "stable").
+fn matches_filter(v: &VersionInfo, channel: &str) -> bool {
+    channel.is_empty() || v.kind.eq_ignore_ascii_case(channel)
+}
+
+/// Indices into `versions` passing the channel filter, then fuzzy-ranked by
+/// `text` (list order kept when `text` is blank).
+fn filter_rows(versions: &[VersionInfo], text: &str, channel: &str) -> Vec<usize> {
+    let in_channel: Vec<usize> = (0..versions.len())
+        .filter(|&i| matches_filter(&versions[i], channel))
+        .collect();
+    fuzzy_filter(text, &in_channel, |&i| &versions[i].ver)
+        .into_iter()
+        .map(|j| in_channel[j])
+        .collect()
+}
+
+/// Lenient semver for game versions: "1.21" reads as "1.21.0" and
+/// "1.20-rc.1" as "1.20.0-rc.1". `None` for anything else.
+pub(crate) fn parse_semver(raw: &str) -> Option<Version> {
+    let (core, pre) = match raw.trim().split_once('-') {
+        Some((core, pre)) => (core, Some(pre)),
+        None => (raw.trim(), None),
+    };
+    let mut nums: Vec<&str> = core.split('.').collect();
+    while nums.len() < 3 {
+        nums.push("0");
+    }
+    let mut fix = nums.join(".");
+    if let Some(pre) = pre {
+        fix.push('-');
+        fix.push_str(pre);
+    }
+    Version::parse(&fix).ok()
+}
+
+/// Whether `ver` is fit to become a folder name: only `[0-9A-Za-z.-]`, and
+/// not a bare `.`/`..` or anything else that could climb out of `versions/`.
+fn safe_version_name(ver: &str) -> bool {
+    !ver.is_empty()
+        && !ver.contains("..")
+        && ver.chars().any(|c| c.is_ascii_alphanumeric())
+        && ver.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+}
+
+/*────────── worker threads ─────────*/
+/// `on_retry` hears about retries after connection failures.
+fn fetch_version_list(on_retry: &mut dyn FnMut(u32)) -> Result<Vec<VersionInfo>, String> {
+    let url = "https://mods.vintagestory.at/api/gameversions";
+    let json = net::get_json_retrying(net::client().get(url), on_retry).map_err(|e| format!("Error: {e}"))?;
+    let arr = json["gameversions"]
+        .as_array()
+        .ok_or_else(|| "Unexpected JSON shape".to_string())?;
+
+    let mut out = Vec::new();
+    for obj in arr {
+        let raw = obj["name"].as_str().unwrap_or("");
+        let name = raw.trim_start_matches('v');
+        // the name ends up in versions/<name>/…; never let the API pick the path
+        if !safe_version_name(name) {
+            eprintln!("gameversions: skipped unusable version name {raw:?}");
+            continue;
+        }
+        let kind = obj["type"]
+            .as_str()
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| {
+                if raw.contains("rc") {
+                    "rc".into()
+                } else if raw.contains("dev") {
+                    "dev".into()
+                } else if raw.contains("pre") {
+                    "preview".into()
+                } else {
+                    "stable".into()
+                }
+            });
+        let flag = |keys: &[&str]| keys.iter().any(|k| obj[*k].as_bool().unwrap_or(false));
+        out.push(VersionInfo {
+            ver: name.to_string(),
+            kind,
+            latest: flag(&["latest", "islatest", "isLatest"]),
+            recommended: flag(&["recommended", "isrecommended", "isRecommended"]),
+        });
+    }
+    // older API responses carry no flags: call the newest stable "latest"
+    if !out.iter().any(|v| v.latest) {
+        if let Some(v) = out
+            .iter_mut()
+            .filter(|v| v.kind == "stable")
+            .max_by(|a, b| VersionPage::version_cmp(&a.ver, &b.ver))
+        {
+            v.latest = true;
+        }
+    }
+    Ok(out)
+}
+
+/// "45 s", "3 min 05 s", "1 h 02 min".
+fn format_eta(d: Duration) -> String {
+    let secs = d.as_secs();
+    match secs {
+        0..=59 => format!("{secs} s"),
+        60..=3599 => format!("{} min {:02} s", secs / 60, secs % 60),
+        _ => format!("{} h {:02} min", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+/// Stability colour for a channel; brighter on dark backgrounds, deeper on light.
+fn kind_color(kind: &str, visuals: &egui::Visuals) -> egui::Color32 {
+    use egui::Color32;
+    let (dark, light) = match kind {
+        "stable" => (Color32::from_rgb(110, 200, 110), Color32::from_rgb(20, 120, 40)),
+        "rc" => (Color32::from_rgb(230, 210, 90), Color32::from_rgb(150, 120, 0)),
+        "preview" => (Color32::from_rgb(240, 150, 70), Color32::from_rgb(190, 90, 0)),
+        "dev" => (Color32::from_rgb(235, 95, 95), Color32::from_rgb(180, 30, 30)),
+        _ => return visuals.text_color(),
+    };
+    if visuals.dark_mode { dark } else { light }
+}
+
+/// Checks that a version folder holds a usable client. The error says what's wrong.
+pub(crate) fn verify_install(ver: &str) -> Result<(), String> {
+    let install = paths::install_dir(ver);
+    let empty = fs::read_dir(&install).map(|mut rd| rd.next().is_none()).unwrap_or(true);
+    if empty {
+        return Err(if paths::archive_path(ver).exists() {
+            "download not finished or not extracted".into()
+        } else {
+            "install folder is missing or empty".into()
+        });
+    }
+    let root = install.join("vintagestory");
+    if launch::find_executable(&root).is_none() {
+        return Err("game executable is missing".into());
+    }
+    if !root.join("assets").is_dir() {
+        return Err("assets folder is missing".into());
+    }
+    Ok(())
+}
+
+/// Size, md5 and date for `ver` from the launcher release feed
+/// (`stable.json`, or `unstable.json` for pre-releases), read leniently.
+fn fetch_release_meta(kind: &str, ver: &str) -> Result<ReleaseMeta, String> {
+    let feed = if kind.eq_ignore_ascii_case("stable") { "stable" } else { "unstable" };
+    let json = net::get_json(net::client().get(format!("https://api.vintagestory.at/{feed}.json")))
+        .map_err(|e| e.to_string())?;
+    let build = &json[ver];
+    if build.is_null() {
+        return Err(format!("v{ver} is not in {feed}.json"));
+    }
+    let key = client_package().feed_key;
+    let file = if build[key].is_object() { &build[key] } else { build };
+    let text = |v: &Value| v.as_str().map(str::to_owned).or_else(|| v.as_u64().map(|n| n.to_string()));
+    Ok(ReleaseMeta {
+        filesize: text(&file["filesize"]).and_then(|s| s.parse().ok()),
+        md5: text(&file["md5"]),
+        sha256: text(&file["sha256"]),
+        date: ["releasedate", "date", "created"].iter().find_map(|k| text(&build[*k]).or_else(|| text(&file[*k]))),
+    })
+}
+
+/// How the client for this OS is published.
+struct ClientPackage {
+    /// archive name is `{prefix}{ver}{suffix}`
+    prefix:   &'static str,
+    suffix:   &'static str,
+    /// entry of a build in the release feed describing that archive
+    feed_key: &'static str,
+}
+
+fn client_package() -> ClientPackage {
+    if cfg!(target_os = "windows") {
+        ClientPackage { prefix: "vs_archive_win-x64_", suffix: ".zip", feed_key: "windowsarchive" }
+    } else if cfg!(target_os = "macos") {
+        ClientPackage { prefix: "vs_client_osx-x64_", suffix: ".tar.gz", feed_key: "mac" }
+    } else {
+        ClientPackage { prefix: "vs_client_linux-x64_", suffix: ".tar.gz", feed_key: "linux" }
+    }
+}
+
+/// This platform's client archive for `ver` under a CDN (or mirror) base URL.
+pub(crate) fn client_url(base: &str, ver: &str) -> String {
+    let ClientPackage { prefix, suffix, .. } = client_package();
+    format!("{base}stable/{prefix}{ver}{suffix}")
+}
+
+/// Fetches and installs `ver`, handing every progress event to `emit`.
+pub(crate) fn download_and_extract(
+    ver: &str,
+    emit: &mut dyn FnMut(ProgressEvent),
+    cancel: &AtomicBool,
 ) -> io::Result<()> {
-    let cdn_base = "https://cdn.vintagestory.at/gamefiles/stable/";
-    let file = format!("vs_client_linux-x64_{}.tar.gz", v.ver);
-    let url = format!("{cdn_base}{file}");
+    let url = client_url(&Config::load().cdn_base(), ver);
+    let meta = published_meta(ver);
+    if let Some(len) = meta.as_ref().and_then(|m| m.filesize) {
+        let have = fs::metadata(paths::archive_path(ver)).map_or(0, |m| m.len());
+        check_space(len, have, disk::free_space(&paths::install_dir(ver))).map_err(io::Error::other)?;
+    }
+    let checksum = meta.and_then(|meta| meta.sha256.or(meta.md5));
+    if checksum.is_none() {
+        emit(ProgressEvent::Unverified);
+    }
 
-    let mut resp = Client::new()
-        .get(&url)
-        .send()
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
-    let total = resp.content_length().unwrap_or(0) as f32;
+    fetch_and_unpack(&url, &paths::archive_path(ver), &paths::install_dir(ver), checksum.as_deref(), emit, cancel)
+}
 
-    let archive_path = VersionPage::archive_path(&v.ver);
-    fs::create_dir_all(archive_path.parent().unwrap())?;
-    let mut dst = fs::File::create(&archive_path)?;
+/// `ver`'s entry in the release feeds, for the archive size and checksum
+/// (SHA-256 where the feed lists one, else MD5). `None` if neither feed
+/// knows the build or the API is unreachable; the install then goes ahead
+/// unverified and without a space check.
+fn published_meta(ver: &str) -> Option<ReleaseMeta> {
+    ["stable", "unstable"]
+        .into_iter()
+        .find_map(|feed| fetch_release_meta(feed, ver).ok())
+}
 
-    let mut downloaded = 0u64;
-    let mut buf = [0u8; 8192];
+/// Room an install from an `archive_len` archive takes: the archive plus
+/// the extracted files, about 2.5× the archive all told.
+fn space_needed(archive_len: u64) -> u64 {
+    archive_len.saturating_mul(5) / 2
+}
 
-    loop {
-        let n = resp.read(&mut buf)?;
-        if n == 0 {
-            break;
+/// Refuses an install that won't fit on the volume: `have` bytes of the
+/// archive are already on disk, `free` is what is left (unknown passes).
+fn check_space(archive_len: u64, have: u64, free: Option<u64>) -> Result<(), String> {
+    let needed = space_needed(archive_len).saturating_sub(have);
+    match free {
+        Some(free) if free < needed => Err(format!(
+            "not enough disk space: needs {}, {} free",
+            disk::human_bytes(needed),
+            disk::human_bytes(free)
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Hashes the file at `path` and compares it to `expected` (hex; 64 digits
+/// for SHA-256, 32 for MD5, case-insensitive).
+pub(crate) fn verify_checksum(path: &Path, expected: &str) -> io::Result<()> {
+    let expected = expected.trim().to_ascii_lowercase();
+    let got = match expected.len() {
+        64 => hash_file::<Sha256>(path)?,
+        32 => hash_file::<Md5>(path)?,
+        _ => {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("unrecognised checksum \"{expected}\"")));
         }
-        dst.write_all(&buf[..n])?;
-        downloaded += n as u64;
-        if total > 0.0 {
-            let _ = tx.send(ProgressEvent::Progress(downloaded as f32 / total));
+    };
+    if got != expected {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("download is corrupt: checksum {got} does not match the published {expected}"),
+        ));
+    }
+    Ok(())
+}
+
+fn hash_file<D: Digest + io::Write>(path: &Path) -> io::Result<String> {
+    let mut hasher = D::new();
+    io::copy(&mut fs::File::open(path)?, &mut hasher)?;
+    Ok(hasher.finalize().iter().map(|b| format!("{b:02x}")).collect())
+}
+
+/// The download + extract pipeline with explicit locations. With a
+/// `checksum`, the archive is verified first; a mismatch removes it and
+/// the install folder so the next try starts clean.
+fn fetch_and_unpack(
+    url: &str,
+    archive_path: &Path,
+    install_dir: &Path,
+    checksum: Option<&str>,
+    emit: &mut dyn FnMut(ProgressEvent),
+    cancel: &AtomicBool,
+) -> io::Result<()> {
+    net::download_file_with_progress(url, archive_path, cancel, emit).map_err(io::Error::other)?;
+
+    if let Some(expected) = checksum {
+        if let Err(e) = verify_checksum(archive_path, expected) {
+            let _ = fs::remove_file(archive_path);
+            let _ = fs::remove_dir_all(install_dir);
+            return Err(e);
         }
     }
 
-    let install_dir = VersionPage::install_dir(&v.ver);
-    fs::create_dir_all(&install_dir)?;
-    let f = fs::File::open(&archive_path)?;
-    uncompress_archive(&f, &install_dir, Ownership::Preserve)
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+    extract::extract_archive(archive_path, install_dir)?;
 
-    let _ = tx.send(ProgressEvent::Finished);
+    emit(ProgressEvent::Finished);
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::DEFAULT_CDN,
+        testutil::{serve_each, serve_once, serve_raw},
+    };
+
+    const FIXTURE: &[u8] = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/vs_client_tiny.tar.gz"
+    ));
+
+    /// Like `serve_once` with no Content-Length; the body ends when the socket closes.
+    fn serve_unsized(body: &'static [u8]) -> String {
+        serve_raw("HTTP/1.1 200 OK\r\nConnection: close\r\n\r\n".into(), body)
+    }
+
+    fn run(url: &str) -> (io::Result<()>, Vec<ProgressEvent>, tempfile::TempDir) {
+        run_with_partial(url, &[])
+    }
+
+    /// Like `run`, with `partial` already sitting in the archive path.
+    fn run_with_partial(url: &str, partial: &[u8]) -> (io::Result<()>, Vec<ProgressEvent>, tempfile::TempDir) {
+        run_checked(url, partial, None)
+    }
+
+    /// Like `run_with_partial`, verifying the archive against `checksum`.
+    fn run_checked(
+        url: &str,
+        partial: &[u8],
+        checksum: Option<&str>,
+    ) -> (io::Result<()>, Vec<ProgressEvent>, tempfile::TempDir) {
+        let tmp = tempfile::tempdir().unwrap();
+        if !partial.is_empty() {
+            fs::write(tmp.path().join("vs_archive.tar.gz"), partial).unwrap();
+        }
+        let mut events = Vec::new();
+        let res = fetch_and_unpack(
+            url,
+            &tmp.path().join("vs_archive.tar.gz"),
+            &tmp.path().join("install"),
+            checksum,
+            &mut |e| events.push(e),
+            &AtomicBool::new(false),
+        );
+        (res, events, tmp)
+    }
+
+    #[test]
+    fn downloads_and_extracts_into_install_dir() {
+        let (res, events, tmp) = run(&serve_once("200 OK", FIXTURE.len(), FIXTURE));
+        res.unwrap();
+
+        let client = tmp.path().join("install/vintagestory");
+        assert!(client.join("Vintagestory").is_file());
+        let version = fs::read_to_string(client.join("assets/version.txt")).unwrap();
+        assert_eq!(version.trim(), "1.0.0-test");
+
+        let fracs: Vec<f32> = events
+            .iter()
+            .filter_map(|e| match e {
+                ProgressEvent::Progress(f) => Some(*f),
+                _ => None,
+            })
+            .collect();
+        assert!(!fracs.is_empty());
+        assert_eq!(fracs.last().copied(), Some(1.0));
+        assert!(matches!(events.last(), Some(ProgressEvent::Finished)));
+    }
+
+    #[test]
+    fn unknown_length_reports_bytes_instead_of_a_fraction() {
+        let (res, events, tmp) = run(&serve_unsized(FIXTURE));
+        res.unwrap();
+        assert!(tmp.path().join("install/vintagestory/Vintagestory").is_file());
+        assert!(!events.iter().any(|e| matches!(e, ProgressEvent::Progress(_))));
+        let last_bytes = events.iter().rev().find_map(|e| match e {
+            ProgressEvent::Bytes(n) => Some(*n),
+            _ => None,
+        });
+        assert_eq!(last_bytes, Some(FIXTURE.len() as u64));
+    }
+
+    #[test]
+    fn http_404_is_an_error_and_installs_nothing() {
+        let (res, events, tmp) = run(&serve_once("404 Not Found", 9, b"not found"));
+        let err = res.unwrap_err();
+        assert!(err.to_string().contains("404"), "{err}");
+        assert!(!tmp.path().join("install").exists());
+        assert!(!events.iter().any(|e| matches!(e, ProgressEvent::Finished)));
+    }
+
+    #[test]
+    fn truncated_body_is_an_error_and_installs_nothing() {
+        let half = &FIXTURE[..FIXTURE.len() / 2];
+        // the retry for the rest is turned down, so the download gives up
+        let url = serve_each(vec![
+            (format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", FIXTURE.len()), half),
+            ("HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".into(), b""),
+        ]);
+        let (res, events, tmp) = run(&url);
+        assert!(res.is_err());
+        assert!(!tmp.path().join("install").exists());
+        assert!(!events.iter().any(|e| matches!(e, ProgressEvent::Finished)));
+    }
+
+    #[test]
+    fn resumes_a_partial_archive_with_range() {
+        let (head, rest) = FIXTURE.split_at(FIXTURE.len() / 2);
+        let (res, events, tmp) =
+            run_with_partial(&serve_once("206 Partial Content", rest.len(), rest), head);
+        res.unwrap();
+        assert!(tmp.path().join("install/vintagestory/Vintagestory").is_file());
+        assert!(matches!(events.last(), Some(ProgressEvent::Finished)));
+        // progress counts the bytes that were already there
+        let first = events.iter().find_map(|e| match e {
+            ProgressEvent::Progress(f) => Some(*f),
+            _ => None,
+        });
+        assert!(first.is_some_and(|f| f >= 0.5), "{first:?}");
+    }
+
+    #[test]
+    fn server_ignoring_range_restarts_the_archive() {
+        let (res, events, tmp) = run_with_partial(&serve_once("200 OK", FIXTURE.len(), FIXTURE), b"stale bytes");
+        res.unwrap();
+        assert_eq!(fs::read(tmp.path().join("vs_archive.tar.gz")).unwrap(), FIXTURE);
+        assert!(matches!(events.last(), Some(ProgressEvent::Finished)));
+    }
+
+    #[test]
+    fn checksums_are_checked_by_length() {
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("abc.txt");
+        fs::write(&file, b"abc").unwrap();
+        verify_checksum(&file, "BA7816BF8F01CFEA414140DE5DAE2223B00361A396177A9CB410FF61F20015AD").unwrap();
+        verify_checksum(&file, "900150983cd24fb0d6963f7d28e17f72").unwrap();
+        let err = verify_checksum(&file, &"0".repeat(64)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(verify_checksum(&file, "xyz").unwrap_err().kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn checksum_mismatch_removes_the_archive_and_installs_nothing() {
+        let bad = "0".repeat(32);
+        let (res, events, tmp) = run_checked(&serve_once("200 OK", FIXTURE.len(), FIXTURE), &[], Some(&bad));
+        assert!(res.unwrap_err().to_string().contains("corrupt"));
+        assert!(!tmp.path().join("vs_archive.tar.gz").exists());
+        assert!(!tmp.path().join("install").exists());
+        assert!(!events.iter().any(|e| matches!(e, ProgressEvent::Finished)));
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn client_url_names_the_linux_archive() {
+        assert_eq!(
+            client_url(DEFAULT_CDN, "1.20.3"),
+            "https://cdn.vintagestory.at/gamefiles/stable/vs_client_linux-x64_1.20.3.tar.gz"
+        );
+    }
+
+    #[test]
+    fn downloads_finish_without_clobbering_each_other() {
+        let mut page = VersionPage::default();
+        let activity = Activity::default();
+        let a = activity.start("a");
+        let b = activity.start("b");
+        page.downloads.push(Download::new("1.20.0".into(), a.task(), Arc::default()));
+        page.downloads.push(Download::new("1.21.0".into(), b.task(), Arc::default()));
+        a.post(ProgressEvent::Progress(0.5));
+        b.post(ProgressEvent::Progress(0.9));
+        b.post(ProgressEvent::Finished);
+
+        page.poll_task(&egui::Context::default(), &activity, &Notifier::default(), &Config::default());
+        assert_eq!(page.downloads.len(), 1);
+        assert!(matches!(page.fetch_state("1.20.0"), Some(Fetch::Running(Some(f))) if f == 0.5));
+        assert!(page.fetch_state("1.21.0").is_none());
+
+        // a worker that vanishes counts as failed, and only it goes
+        drop(a);
+        page.poll_task(&egui::Context::default(), &activity, &Notifier::default(), &Config::default());
+        assert!(page.downloads.is_empty());
+    }
+
+    #[test]
+    fn unverified_installs_are_remembered_until_they_finish() {
+        let activity = Activity::default();
+        let worker = activity.start("download");
+        let mut d = Download::new("1.20.0".into(), worker.task(), Arc::default());
+        worker.post(ProgressEvent::Unverified);
+        worker.post(ProgressEvent::Progress(0.5));
+        assert!(d.drain(&activity).is_none());
+        worker.post(ProgressEvent::Finished);
+        assert!(matches!(d.drain(&activity), Some(ProgressEvent::Finished)));
+        assert!(d.unverified);
+    }
+
+    #[test]
+    fn installs_need_room_for_archive_and_files() {
+        assert_eq!(space_needed(400), 1000);
+        assert!(check_space(400, 0, Some(1000)).is_ok());
+        let err = check_space(400, 0, Some(999)).unwrap_err();
+        assert!(err.contains("not enough disk space"), "{err}");
+        // a partial archive already holds its share
+        assert!(check_space(400, 100, Some(900)).is_ok());
+        assert!(check_space(400, 0, None).is_ok());
+    }
+
+    #[test]
+    fn enter_on_a_highlighted_row_does_not_play() {
+        use crate::{pages::harness, uistate::{self, Shortcut}};
+
+        let ctx = harness::context();
+        let mut page = VersionPage { versions: sample(), ..VersionPage::default() };
+        assert!(!page.consumes_enter());
+        let enter = || vec![harness::press(egui::Key::Enter)];
+        assert_eq!(harness::frame(&ctx, enter(), |ctx| uistate::shortcut(ctx, page.consumes_enter())), Some(Shortcut::Play));
+        page.selected_row = Some(0);
+        assert!(page.consumes_enter());
+        assert_eq!(harness::frame(&ctx, enter(), |ctx| uistate::shortcut(ctx, page.consumes_enter())), None);
+    }
+
+    #[test]
+    fn eta_reads_naturally() {
+        assert_eq!(format_eta(Duration::from_secs(45)), "45 s");
+        assert_eq!(format_eta(Duration::from_secs(185)), "3 min 05 s");
+        assert_eq!(format_eta(Duration::from_secs(3720)), "1 h 02 min");
+    }
+
+    /*── filter / semver ──*/
+    fn info(ver: &str, kind: &str) -> VersionInfo {
+        VersionInfo { ver: ver.into(), kind: kind.into(), latest: false, recommended: false }
+    }
+
+    fn sample() -> Vec<VersionInfo> {
+        vec![info("1.20.0", "stable"), info("1.21.0-rc.1", "rc"), info("1.21.0", "Stable")]
+    }
+
+    #[test]
+    fn empty_filters_show_everything_in_order() {
+        assert_eq!(filter_rows(&sample(), "", ""), vec![0, 1, 2]);
+        assert_eq!(filter_rows(&sample(), "   ", ""), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn channel_mismatch_is_filtered_out() {
+        assert_eq!(filter_rows(&sample(), "", "rc"), vec![1]);
+        assert_eq!(filter_rows(&sample(), "", "stable"), vec![0, 2]);
+        assert!(filter_rows(&sample(), "", "dev").is_empty());
+        assert!(!matches_filter(&info("1.21.0", "rc"), "stable"));
+    }
+
+    #[test]
+    fn text_filter_combines_with_channel() {
+        assert_eq!(filter_rows(&sample(), "1.21", "stable"), vec![2]);
+        assert!(filter_rows(&sample(), "1.19", "").is_empty());
+    }
+
+    #[test]
+    fn short_versions_pad_to_three_parts() {
+        assert_eq!(parse_semver("1.21"), parse_semver("1.21.0"));
+        assert_eq!(parse_semver("1"), Some(Version::new(1, 0, 0)));
+        // equal semver still gets a stable, total order
+        assert_eq!(VersionPage::version_cmp("1.21", "1.21.0"), Ordering::Less);
+    }
+
+    #[test]
+    fn prerelease_sorts_before_its_release() {
+        let rc = parse_semver("1.20-rc.1").unwrap();
+        assert_eq!(rc.pre.as_str(), "rc.1");
+        assert!(rc < parse_semver("1.20.0").unwrap());
+        assert_eq!(VersionPage::version_cmp("1.20-rc.1", "1.20.0"), Ordering::Less);
+        assert_eq!(VersionPage::version_cmp("1.20.0-rc.2", "1.20.0-rc.10"), Ordering::Less);
+    }
+
+    #[test]
+    fn junk_does_not_parse_and_sorts_first() {
+        for junk in ["", "latest", "1.x.3", "1.2.3.4", "v1.2.3"] {
+            assert_eq!(parse_semver(junk), None, "{junk:?}");
+        }
+        assert_eq!(VersionPage::version_cmp("latest", "0.0.1"), Ordering::Less);
+    }
+
+    #[test]
+    fn version_names_with_path_parts_are_rejected() {
+        for ok in ["1.20.11", "1.21.0-rc.2", "1.19.8-pre.1"] {
+            assert!(safe_version_name(ok), "{ok}");
+        }
+        for bad in ["", ".", "..", "../../etc", "1.20/../x", "1.20\\x", "1.20 x", "/abs", "1..2"] {
+            assert!(!safe_version_name(bad), "{bad}");
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! paths.rs – every on-disk location the launcher uses, in one place
+//!
+//! `settings.json` always sits in `default_base_dir()`; it may name another
+//! folder as `base_dir()`. Layout under `base_dir()`:
+//! ```text
+//! versions/<ver>/install/        extracted client
+//! versions/<ver>/vs_archive.tar.gz
+//! instances/<name>/              per-instance --dataPath (unless the
+//!                                instance was created under a custom root)
+//! instances.json
+//! cache/                         re-downloadable API data
+//! backups/
+//! ```
+use std::{
+    env,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+/// data folder from the settings, fixed for the run by `use_base_dir`
+static BASE_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+#[cfg(test)]
+thread_local! {
+    /// data folder of the running test, see `use_test_base_dir`
+    static TEST_BASE: std::cell::RefCell<Option<PathBuf>> = const { std::cell::RefCell::new(None) };
+}
+
+/// Per-user data root: `$XDG_DATA_HOME`, else `$HOME/.local/share` (the
+/// platform equivalent elsewhere). `None` when no absolute home can be found;
+/// `main` refuses to start then rather than writing into the CWD.
+///
+/// Tests get one temporary folder for the whole run instead, so nothing a
+/// test or one of its workers does reaches the real data.
+pub fn data_home() -> Option<PathBuf> {
+    #[cfg(test)]
+    {
+        static TEST_HOME: OnceLock<tempfile::TempDir> = OnceLock::new();
+        return Some(TEST_HOME.get_or_init(|| tempfile::tempdir().unwrap()).path().to_path_buf());
+    }
+    #[allow(unreachable_code)]
+    dirs::data_local_dir()
+        .or_else(|| home().map(|h| h.join(".local/share")))
+        .filter(|p| p.is_absolute())
+}
+
+fn home() -> Option<PathBuf> {
+    env::var_os("HOME")
+        .filter(|h| !h.is_empty())
+        .map(PathBuf::from)
+        .or_else(dirs::home_dir)
+}
+
+/// The launcher's own folder, e.g. `~/.local/share/vs_launcher`. Holds
+/// `settings.json`, and everything else unless the settings move it.
+pub fn default_base_dir() -> PathBuf {
+    data_home()
+        .expect("no home directory (checked at startup)")
+        .join("vs_launcher")
+}
+
+/// Root of the launcher's data: the folder chosen in the settings, else
+/// `default_base_dir()`.
+pub fn base_dir() -> PathBuf {
+    #[cfg(test)]
+    if let Some(dir) = TEST_BASE.with(|b| b.borrow().clone()) {
+        return dir;
+    }
+    BASE_OVERRIDE.get().cloned().unwrap_or_else(default_base_dir)
+}
+
+/// Sets the data folder for this run; only the first call counts, so a
+/// changed setting applies after a restart.
+pub fn use_base_dir(dir: Option<PathBuf>) {
+    if let Some(dir) = dir {
+        let _ = BASE_OVERRIDE.set(dir);
+    }
+}
+
+/// Points `base_dir()` at `dir` for the rest of the calling test, so tests
+/// that look at instance or version folders don't see each other's. Per
+/// thread: workers the test starts see the run's shared test folder (see
+/// `data_home`), never the real one.
+#[cfg(test)]
+pub fn use_test_base_dir(dir: &Path) {
+    TEST_BASE.with(|b| *b.borrow_mut() = Some(dir.to_path_buf()));
+}
+
+/// Whether `base` already holds versions or instances.
+pub fn holds_data(base: &Path) -> bool {
+    base.join("versions").is_dir() || base.join("instances.json").is_file()
+}
+
+pub fn versions_dir() -> PathBuf {
+    base_dir().join("versions")
+}
+/// downloaded client archive, kept next to the install
+pub fn archive_path(ver: &str) -> PathBuf {
+    versions_dir().join(ver).join("vs_archive.tar.gz")
+}
+pub fn install_dir(ver: &str) -> PathBuf {
+    versions_dir().join(ver).join("install")
+}
+
+/// default parent of the instances' data folders
+pub fn instances_dir() -> PathBuf {
+    base_dir().join("instances")
+}
+/// data folder of instance `name`, under `root` or else `instances_dir()`
+pub fn instance_dir(root: Option<&Path>, name: &str) -> PathBuf {
+    root.map_or_else(instances_dir, Path::to_path_buf).join(name)
+}
+pub fn instances_file() -> PathBuf {
+    base_dir().join("instances.json")
+}
+/// last tab and footer selection
+pub fn ui_state_file() -> PathBuf {
+    base_dir().join("ui_state.json")
+}
+pub fn settings_file() -> PathBuf {
+    default_base_dir().join("settings.json")
+}
+
+/// re-downloadable data (API responses); safe to wipe
+pub fn cache_dir() -> PathBuf {
+    base_dir().join("cache")
+}
+pub fn backups_dir() -> PathBuf {
+    base_dir().join("backups")
+}
@@ -0,0 +1,87 @@
+//! preflight.rs – "Validate" checklist run before launching an instance,
+//! and the startup check that the launcher can write where it keeps data
+use std::{fs, path::Path};
+
+use crate::{config::Config, launch, modinfo, modlock::ModLock, pages::instances::Instance, pages::versions, paths};
+
+/// One line of the report.
+pub struct Check {
+    pub label:  &'static str,
+    /// `Err` carries what went wrong
+    pub result: Result<(), String>,
+}
+
+/// Runs every check for `inst`. Touches the disk (mods are opened to read
+/// their modinfo), so call it off the UI thread.
+pub fn validate(inst: &Instance) -> Vec<Check> {
+    let mods = modinfo::list_mods(&inst.mods_dir());
+    let unreadable: Vec<String> = mods
+        .iter()
+        .filter(|m| m.info.is_err())
+        .map(|m| m.file_name())
+        .collect();
+    let missing_deps = modinfo::check_dependencies(&mods, &inst.version);
+    let drift = ModLock::load(inst).drift(&mods);
+
+    vec![
+        Check {
+            label: "Game version installed",
+            // a custom executable doesn't need the managed install
+            result: match &inst.custom_executable {
+                Some(_) => Ok(()),
+                None => versions::verify_install(&inst.version).map_err(|e| format!("v{}: {e}", inst.version)),
+            },
+        },
+        Check { label: "Executable runnable", result: executable_ok(inst) },
+        Check { label: "Data folder writable", result: writable(&inst.data_dir()) },
+        Check {
+            label: "Mods readable",
+            result: if unreadable.is_empty() {
+                Ok(())
+            } else {
+                Err(format!("unreadable modinfo: {}", unreadable.join(", ")))
+            },
+        },
+        Check {
+            label: "Mod dependencies satisfied",
+            result: if missing_deps.is_empty() { Ok(()) } else { Err(missing_deps.join("\n")) },
+        },
+        Check {
+            label: "Mods match modlock.json",
+            result: if drift.is_empty() { Ok(()) } else { Err(drift.join("\n")) },
+        },
+    ]
+}
+
+fn executable_ok(inst: &Instance) -> Result<(), String> {
+    let (bin, _) = launch::executable(inst)?;
+    let meta = fs::metadata(&bin).map_err(|e| e.to_string())?;
+    // launch() sets the bit itself, but only if it's allowed to
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if meta.is_file() && meta.permissions().mode() & 0o111 == 0 && meta.permissions().readonly() {
+            return Err(format!("{} is not executable and read-only", bin.display()));
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = meta;
+    Ok(())
+}
+
+/// Creates `dir` if needed, then writes and removes a probe file in it.
+fn writable(dir: &Path) -> Result<(), String> {
+    fs::create_dir_all(dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+    let probe = dir.join(".vs_launcher_write_test");
+    fs::write(&probe, b"ok").map_err(|e| format!("{}: {e}", dir.display()))?;
+    let _ = fs::remove_file(probe);
+    Ok(())
+}
+
+/// Startup check: the launcher's data folder and the custom instances
+/// folder from the settings (if any). One message per folder that fails.
+pub fn storage_problems(config: &Config) -> Vec<String> {
+    let mut dirs = vec![paths::base_dir()];
+    dirs.extend(config.instances_root());
+    dirs.iter().filter_map(|d| writable(d).err()).collect()
+}
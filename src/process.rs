@@ -0,0 +1,169 @@
+//! process.rs – games started from the GUI and the output they leave behind
+use std::{
+    collections::VecDeque,
+    io::{BufRead, BufReader},
+    path::Path,
+    process::{Child, ExitStatus},
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
+
+use sysinfo::{Pid, Signal, System};
+
+/// How many stderr lines are kept per running game.
+const TAIL_LINES: usize = 200;
+/// How many lines of combined stdout + stderr the live log keeps.
+const LOG_LINES: usize = 2000;
+
+/// Shared, bounded line buffer filled by the pipe readers; outlives the game.
+pub type LogBuffer = Arc<Mutex<VecDeque<String>>>;
+
+pub struct GameProcess {
+    child:   Child,
+    stderr:  LogBuffer,
+    /// stdout and stderr interleaved as they arrive
+    log:     LogBuffer,
+    started: SystemTime,
+}
+
+impl GameProcess {
+    /// Takes ownership of a spawned child and starts draining its piped
+    /// stdout and stderr.
+    pub fn new(mut child: Child) -> Self {
+        let stderr = LogBuffer::default();
+        let log = LogBuffer::default();
+        if let Some(pipe) = child.stderr.take() {
+            let (tail, log) = (stderr.clone(), log.clone());
+            thread::spawn(move || {
+                for line in BufReader::new(pipe).lines().map_while(Result::ok) {
+                    push_bounded(&log, line.clone(), LOG_LINES);
+                    push_bounded(&tail, line, TAIL_LINES);
+                }
+            });
+        }
+        if let Some(pipe) = child.stdout.take() {
+            let log = log.clone();
+            thread::spawn(move || {
+                for line in BufReader::new(pipe).lines().map_while(Result::ok) {
+                    push_bounded(&log, line, LOG_LINES);
+                }
+            });
+        }
+        Self { child, stderr, log, started: SystemTime::now() }
+    }
+
+    /// The live log, for a viewer that may stay open after the game exits.
+    pub fn log(&self) -> LogBuffer {
+        self.log.clone()
+    }
+
+    /// `Some(status)` once the game has exited.
+    pub fn try_exit(&mut self) -> Option<ExitStatus> {
+        self.child.try_wait().ok().flatten()
+    }
+
+    /// Kills the game and reaps it.
+    pub fn kill(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+
+    /// Asks the game to quit (SIGTERM, so it can save); `false` if the
+    /// signal could not be sent.
+    fn ask_to_quit(&self) -> bool {
+        let pid = Pid::from_u32(self.pid());
+        let mut sys = System::new();
+        sys.refresh_process(pid) && sys.process(pid).and_then(|p| p.kill_with(Signal::Term)) == Some(true)
+    }
+
+    pub fn pid(&self) -> u32 {
+        self.child.id()
+    }
+
+    /// wall-clock launch time, to tell which files the session wrote
+    pub fn started(&self) -> SystemTime {
+        self.started
+    }
+
+    pub fn stderr_tail(&self) -> Vec<String> {
+        self.stderr.lock().unwrap().iter().cloned().collect()
+    }
+}
+
+/// Asks every game in `games` to quit at once, then kills the ones still
+/// running after `grace`, which they all share. Blocks until all are gone.
+pub fn stop_all(games: Vec<GameProcess>, grace: Duration) {
+    let mut waiting = Vec::new();
+    for mut game in games {
+        if game.ask_to_quit() {
+            waiting.push(game);
+        } else {
+            game.kill();
+        }
+    }
+    let deadline = Instant::now() + grace;
+    while !waiting.is_empty() && Instant::now() < deadline {
+        waiting.retain_mut(|g| g.try_exit().is_none());
+        thread::sleep(Duration::from_millis(50));
+    }
+    for mut game in waiting {
+        game.kill();
+    }
+}
+
+fn push_bounded(buf: &LogBuffer, line: String, cap: usize) {
+    let mut buf = buf.lock().unwrap();
+    if buf.len() == cap {
+        buf.pop_front();
+    }
+    buf.push_back(line);
+}
+
+/// Best-effort scan for a Vintage Story client started outside the launcher
+/// (terminal, another launcher, …) that uses `data_dir` as its `--dataPath`.
+/// `ours` are pids the launcher already tracks. Returns the pid found.
+pub fn external_game_using(data_dir: &Path, ours: &[u32]) -> Option<u32> {
+    let mut sys = System::new();
+    sys.refresh_processes();
+    sys.processes().iter().find_map(|(pid, p)| {
+        let pid = pid.as_u32();
+        if ours.contains(&pid) {
+            return None;
+        }
+        let cmd = p.cmd();
+        // native apphost ("Vintagestory") or `dotnet Vintagestory.dll`
+        let is_game = p.name().to_lowercase().starts_with("vintagestory")
+            || cmd.iter().any(|a| a.ends_with("Vintagestory.dll"));
+        let same_data = cmd
+            .windows(2)
+            .any(|w| w[0].eq_ignore_ascii_case("--dataPath") && Path::new(&w[1]) == data_dir);
+        (is_game && same_data).then_some(pid)
+    })
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::process::Command;
+
+    use super::*;
+
+    fn spawn(script: &str) -> GameProcess {
+        GameProcess::new(Command::new("sh").args(["-c", script]).spawn().unwrap())
+    }
+
+    #[test]
+    fn games_share_one_grace_period() {
+        // both ignore SIGTERM, so both sit out the grace period – together
+        let stubborn = vec![spawn("trap '' TERM; exec sleep 30"), spawn("trap '' TERM; exec sleep 30")];
+        let start = Instant::now();
+        stop_all(stubborn, Duration::from_secs(1));
+        let took = start.elapsed();
+        assert!(took >= Duration::from_secs(1) && took < Duration::from_millis(1900), "{took:?}");
+
+        let polite = vec![spawn("exec sleep 30"), spawn("exec sleep 30")];
+        let start = Instant::now();
+        stop_all(polite, Duration::from_secs(5));
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+}
@@ -0,0 +1,146 @@
+//! schema.rs – versioned JSON files for per-instance user state
+//!
+//! Every file written through here carries a top-level `schema_version`.
+//! Loading runs the type's migration from whatever version the file has
+//! (files from before versioning count as 0). Saving over a file written
+//! by a newer launcher first keeps it as `<name>.schema<N>.bak`, so going
+//! back a release never silently drops what the newer one stored. A file
+//! that doesn't parse loads as the default, and the next save keeps it as
+//! `<name>.unreadable.bak` before writing, so a hand edit gone wrong can be
+//! recovered.
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+const KEY: &str = "schema_version";
+
+pub trait Versioned: Serialize + DeserializeOwned + Default {
+    /// layout this build reads and writes
+    const SCHEMA: u64;
+
+    /// Rewrites `raw`, stored with schema `from`, into the current layout.
+    /// Only called when `from < SCHEMA`.
+    fn migrate(from: u64, raw: Value) -> Value {
+        let _ = from;
+        raw
+    }
+}
+
+fn version_of(raw: &Value) -> u64 {
+    raw.get(KEY).and_then(Value::as_u64).unwrap_or(0)
+}
+
+/// The file at `path`, migrated; the default if it is missing or unreadable.
+pub fn load<T: Versioned>(path: &Path) -> T {
+    fs::read_to_string(path).ok().and_then(|txt| parse(&txt)).unwrap_or_default()
+}
+
+fn parse<T: Versioned>(txt: &str) -> Option<T> {
+    let raw: Value = serde_json::from_str(txt).ok()?;
+    let from = version_of(&raw);
+    let raw = if from < T::SCHEMA { T::migrate(from, raw) } else { raw };
+    serde_json::from_value(raw).ok()
+}
+
+/// Writes `value` with the current `schema_version`.
+pub fn save<T: Versioned>(path: &Path, value: &T) -> io::Result<()> {
+    let mut raw = serde_json::to_value(value).map_err(io::Error::other)?;
+    if let Value::Object(map) = &mut raw {
+        map.insert(KEY.into(), T::SCHEMA.into());
+    }
+    if let Ok(txt) = fs::read_to_string(path) {
+        if parse::<T>(&txt).is_none() {
+            fs::write(path.with_file_name(format!("{}.unreadable.bak", file_name(path))), &txt)?;
+        }
+    }
+    if let Some(newer) = on_disk_version(path).filter(|&v| v > T::SCHEMA) {
+        fs::copy(path, backup_path(path, newer))?;
+    }
+    if let Some(p) = path.parent() { fs::create_dir_all(p)?; }
+    fs::write(path, serde_json::to_string_pretty(&raw).map_err(io::Error::other)?)
+}
+
+fn on_disk_version(path: &Path) -> Option<u64> {
+    let raw: Value = serde_json::from_str(&fs::read_to_string(path).ok()?).ok()?;
+    Some(version_of(&raw))
+}
+
+fn backup_path(path: &Path, version: u64) -> PathBuf {
+    path.with_file_name(format!("{}.schema{version}.bak", file_name(path)))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    /// v1 called the field `old`; v2 renamed it to `new`.
+    #[derive(Serialize, Deserialize, Default, PartialEq, Debug)]
+    struct Sample {
+        #[serde(default)]
+        new: String,
+    }
+
+    impl Versioned for Sample {
+        const SCHEMA: u64 = 2;
+
+        fn migrate(from: u64, mut raw: Value) -> Value {
+            if from < 2 {
+                if let Some(old) = raw.as_object_mut().and_then(|m| m.remove("old")) {
+                    raw["new"] = old;
+                }
+            }
+            raw
+        }
+    }
+
+    #[test]
+    fn saved_files_carry_the_schema_version() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("sample.json");
+        save(&path, &Sample { new: "x".into() }).unwrap();
+        let raw: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(raw[KEY], 2);
+        assert_eq!(load::<Sample>(&path), Sample { new: "x".into() });
+    }
+
+    #[test]
+    fn older_files_are_migrated_on_load() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("sample.json");
+        fs::write(&path, r#"{"old": "kept"}"#).unwrap();
+        assert_eq!(load::<Sample>(&path), Sample { new: "kept".into() });
+    }
+
+    #[test]
+    fn newer_files_are_backed_up_before_overwriting() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("sample.json");
+        fs::write(&path, r#"{"schema_version": 7, "new": "a", "extra": 1}"#).unwrap();
+        save(&path, &Sample { new: "b".into() }).unwrap();
+        let backup = fs::read_to_string(tmp.path().join("sample.json.schema7.bak")).unwrap();
+        assert!(backup.contains("extra"));
+    }
+
+    #[test]
+    fn malformed_files_are_kept_before_overwriting() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("sample.json");
+        for broken in [r#"{"new": "a","#, r#"{"new": 5}"#] {
+            fs::write(&path, broken).unwrap();
+            assert_eq!(load::<Sample>(&path), Sample::default());
+            save(&path, &Sample { new: "b".into() }).unwrap();
+            assert_eq!(fs::read_to_string(tmp.path().join("sample.json.unreadable.bak")).unwrap(), broken);
+            assert_eq!(load::<Sample>(&path), Sample { new: "b".into() });
+        }
+    }
+}
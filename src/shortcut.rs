@@ -0,0 +1,78 @@
+//! shortcut.rs – per-instance `.desktop` entries (Linux application menu)
+use std::{fs, io, path::PathBuf};
+
+use crate::{pages::instances::Instance, paths};
+
+/// Writes `~/.local/share/applications/vs_launcher-<name>.desktop`, which runs
+/// `vs_launcher launch <name>`. Returns the written path.
+pub fn create_desktop_entry(inst: &Instance) -> io::Result<PathBuf> {
+    let exe = std::env::current_exe()?;
+    let apps = paths::data_home()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no local data dir"))?
+        .join("applications");
+    fs::create_dir_all(&apps)?;
+
+    let game_icon = paths::install_dir(&inst.version).join("vintagestory/assets/gameicon.xpm");
+    let icon = if game_icon.exists() {
+        game_icon.to_string_lossy().into_owned()
+    } else {
+        "vintagestory".into()
+    };
+
+    let exec = [exe.to_string_lossy().as_ref(), "launch", inst.name.as_str()]
+        .iter()
+        .map(|a| quote_exec_arg(a))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let entry = format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name={}\n\
+         Comment=Vintage Story v{}\n\
+         Exec={}\n\
+         Icon={}\n\
+         Terminal=false\n\
+         Categories=Game;\n",
+        escape_value(&format!("Vintage Story – {}", inst.name)),
+        escape_value(&inst.version),
+        escape_value(&exec),
+        escape_value(&icon),
+    );
+
+    let path = apps.join(format!("vs_launcher-{}.desktop", file_stem(&inst.name)));
+    fs::write(&path, entry)?;
+    Ok(path)
+}
+
+/// Quotes one `Exec` argument per the desktop-entry spec: wrap in double quotes,
+/// backslash-escape `"` `` ` `` `$` `\`, and double `%` so it isn't a field code.
+fn quote_exec_arg(arg: &str) -> String {
+    let mut out = String::from("\"");
+    for c in arg.chars() {
+        match c {
+            '"' | '`' | '$' | '\\' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '%' => out.push_str("%%"),
+            _ => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Escapes a string value (`\` `\n` `\t` `\r`) – applied on top of Exec quoting.
+fn escape_value(v: &str) -> String {
+    v.replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t")
+        .replace('\r', "\\r")
+}
+
+fn file_stem(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
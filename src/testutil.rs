@@ -0,0 +1,53 @@
+//! testutil.rs – fixtures shared by the unit tests
+use std::{
+    io::{Read, Write},
+    net::TcpListener,
+    path::Path,
+    thread::{self, JoinHandle},
+};
+
+use crate::pages::instances::Instance;
+
+/// Instance "Test" on 1.20.0 whose game data lives in `data`.
+pub(crate) fn instance(data: &Path) -> Instance {
+    serde_json::from_value(serde_json::json!({
+        "id": 1, "name": "Test", "version": "1.20.0", "external_data": data,
+    }))
+    .unwrap()
+}
+
+/// Serves one canned HTTP response and returns the URL to hit.
+pub(crate) fn serve_once(status: &str, declared_len: usize, body: &'static [u8]) -> String {
+    serve_raw(
+        format!("HTTP/1.1 {status}\r\nContent-Length: {declared_len}\r\nConnection: close\r\n\r\n"),
+        body,
+    )
+}
+
+pub(crate) fn serve_raw(head: String, body: &'static [u8]) -> String {
+    serve_each(vec![(head, body)])
+}
+
+/// Answers one connection per `(head, body)`, in order.
+pub(crate) fn serve_each(responses: Vec<(String, &'static [u8])>) -> String {
+    serve_recorded(responses).0
+}
+
+/// Like `serve_each`; the handle yields the requests it answered.
+pub(crate) fn serve_recorded(responses: Vec<(String, &'static [u8])>) -> (String, JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = thread::spawn(move || {
+        let mut requests = Vec::new();
+        for (head, body) in responses {
+            let (mut sock, _) = listener.accept().unwrap();
+            let mut req = [0u8; 1024];
+            let n = sock.read(&mut req).unwrap_or(0);
+            requests.push(String::from_utf8_lossy(&req[..n]).into_owned());
+            let _ = sock.write_all(head.as_bytes());
+            let _ = sock.write_all(body);
+        }
+        requests
+    });
+    (format!("http://{addr}/download"), server)
+}
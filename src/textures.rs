@@ -0,0 +1,63 @@
+//! textures.rs – decoded images uploaded to egui once and reused
+use std::{collections::HashMap, path::Path};
+
+use eframe::egui::{self, ColorImage, TextureHandle, TextureOptions};
+
+/// Texture handles keyed by source (file path, URL, …). Failed loads are
+/// remembered as `None` so a broken file isn't re-decoded every frame.
+#[derive(Default)]
+pub struct TextureCache {
+    map: HashMap<String, Option<TextureHandle>>,
+}
+
+impl TextureCache {
+    /// Loads (once) and returns the texture for an image file.
+    pub fn file(&mut self, ctx: &egui::Context, path: &Path) -> Option<&TextureHandle> {
+        let key = path.to_string_lossy().into_owned();
+        self.map
+            .entry(key.clone())
+            .or_insert_with(|| {
+                let bytes = std::fs::read(path).ok()?;
+                let img = decode(&bytes)?;
+                Some(ctx.load_texture(key, img, TextureOptions::LINEAR))
+            })
+            .as_ref()
+    }
+
+    /// Drops a cached entry so the next lookup reloads it.
+    pub fn forget(&mut self, path: &Path) {
+        self.map.remove(path.to_string_lossy().as_ref());
+    }
+}
+
+/// PNG/JPEG bytes → egui image.
+pub fn decode(bytes: &[u8]) -> Option<ColorImage> {
+    image::load_from_memory(bytes).ok().map(decode_image)
+}
+
+/// Decoded image → egui image.
+pub fn decode_image(img: image::DynamicImage) -> ColorImage {
+    let img = img.to_rgba8();
+    let size = [img.width() as usize, img.height() as usize];
+    ColorImage::from_rgba_unmultiplied(size, img.as_raw())
+}
+
+/// Square thumbnail, or a lettered placeholder when there is no texture.
+pub fn avatar(ui: &mut egui::Ui, tex: Option<&TextureHandle>, name: &str, side: f32) -> egui::Response {
+    let size = egui::vec2(side, side);
+    if let Some(tex) = tex {
+        return ui.add(egui::Image::from_texture(tex).fit_to_exact_size(size).rounding(4.0));
+    }
+    let (rect, resp) = ui.allocate_exact_size(size, egui::Sense::hover());
+    let visuals = ui.visuals();
+    ui.painter().rect_filled(rect, 4.0, visuals.faint_bg_color);
+    let letter = name.chars().next().map(|c| c.to_uppercase().to_string()).unwrap_or_default();
+    ui.painter().text(
+        rect.center(),
+        egui::Align2::CENTER_CENTER,
+        letter,
+        egui::FontId::proportional(side * 0.55),
+        visuals.weak_text_color(),
+    );
+    resp
+}
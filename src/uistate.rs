@@ -0,0 +1,143 @@
+//! uistate.rs – where the window was left (tab, footer instance), written
+//! on exit and restored at startup
+use std::{fs, io, path::Path};
+
+use eframe::egui::{self, Key, Modifiers};
+use serde::{Deserialize, Serialize};
+
+use crate::paths;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum View {
+    #[default]
+    Home,
+    Versions,
+    Instances,
+    Mods,
+    Settings,
+}
+
+impl View {
+    /// Tabs in menu order, as Ctrl+1… picks them.
+    pub const ALL: [View; 5] = [View::Home, View::Versions, View::Instances, View::Mods, View::Settings];
+
+    /// "Ctrl+3" (⌘ on macOS), for the menu tooltips.
+    pub fn shortcut_text(self, ctx: &egui::Context) -> String {
+        let n = View::ALL.iter().position(|v| *v == self).unwrap_or(0) + 1;
+        let key = [Key::Num1, Key::Num2, Key::Num3, Key::Num4, Key::Num5][n - 1];
+        ctx.format_shortcut(&egui::KeyboardShortcut::new(Modifiers::COMMAND, key))
+    }
+}
+
+/// What the global shortcuts asked for this frame.
+#[derive(PartialEq, Debug)]
+pub enum Shortcut {
+    Go(View),
+    /// play the footer's instance
+    Play,
+}
+
+/// Ctrl+1…5 (⌘ on macOS) picks a tab, Enter plays the footer's instance.
+/// Nothing fires while a text field has focus; `enter_taken` leaves Enter
+/// to a page that is using it (a highlighted list row), and so does any
+/// open window, since dialogs don't take keyboard focus.
+pub fn shortcut(ctx: &egui::Context, enter_taken: bool) -> Option<Shortcut> {
+    if ctx.memory(|m| m.focused().is_some()) {
+        return None;
+    }
+    let window_open = ctx.memory(|m| m.areas().visible_layer_ids().iter().any(|l| l.order == egui::Order::Middle));
+    let enter_taken = enter_taken || window_open;
+    let keys = [Key::Num1, Key::Num2, Key::Num3, Key::Num4, Key::Num5];
+    ctx.input_mut(|i| {
+        if let Some(n) = keys.iter().position(|k| i.consume_key(Modifiers::COMMAND, *k)) {
+            return Some(Shortcut::Go(View::ALL[n]));
+        }
+        (!enter_taken && i.key_pressed(Key::Enter) && i.modifiers.is_none()).then_some(Shortcut::Play)
+    })
+}
+
+/// Unknown or missing keys fall back to the defaults (Home, nothing selected).
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default, Debug)]
+#[serde(default)]
+pub struct UiState {
+    pub view:        View,
+    /// footer selection, by instance id; may name a deleted instance
+    pub selected_id: Option<u64>,
+}
+
+impl UiState {
+    pub fn load() -> Self {
+        Self::read(&paths::ui_state_file())
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        self.write(&paths::ui_state_file())
+    }
+
+    fn read(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|txt| serde_json::from_str(&txt).ok())
+            .unwrap_or_default()
+    }
+
+    fn write(&self, path: &Path) -> io::Result<()> {
+        if let Some(p) = path.parent() { fs::create_dir_all(p)?; }
+        fs::write(path, serde_json::to_string_pretty(self).map_err(io::Error::other)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pages::harness;
+
+    fn keys(ctx: &egui::Context, events: Vec<egui::Event>, enter_taken: bool) -> Option<Shortcut> {
+        harness::frame(ctx, events, |ctx| shortcut(ctx, enter_taken))
+    }
+
+    #[test]
+    fn shortcuts_switch_tabs_and_play() {
+        let ctx = harness::context();
+        assert_eq!(keys(&ctx, vec![harness::press_with(Key::Num3, Modifiers::COMMAND)], false), Some(Shortcut::Go(View::Instances)));
+        assert_eq!(keys(&ctx, vec![harness::press(Key::Num3)], false), None);
+        assert_eq!(keys(&ctx, vec![harness::press(Key::Enter)], false), Some(Shortcut::Play));
+        assert_eq!(keys(&ctx, vec![harness::press(Key::Enter)], true), None);
+    }
+
+    #[test]
+    fn shortcuts_stay_out_of_text_fields() {
+        let ctx = harness::context();
+        let id = egui::Id::new("search");
+        harness::frame(&ctx, Vec::new(), |ctx| ctx.memory_mut(|m| m.request_focus(id)));
+        let events = vec![harness::press(Key::Enter), harness::press_with(Key::Num2, Modifiers::COMMAND)];
+        assert_eq!(keys(&ctx, events, false), None);
+    }
+
+    #[test]
+    fn enter_is_left_to_open_dialogs() {
+        let ctx = harness::context();
+        harness::frame(&ctx, Vec::new(), |ctx| {
+            egui::Window::new("Delete instance?").show(ctx, |ui| ui.label("Sure?"));
+        });
+        assert_eq!(keys(&ctx, vec![harness::press(Key::Enter)], false), None);
+        // tabs still switch
+        let ctrl_1 = harness::press_with(Key::Num1, Modifiers::COMMAND);
+        assert_eq!(keys(&ctx, vec![ctrl_1], false), Some(Shortcut::Go(View::Home)));
+    }
+
+    #[test]
+    fn state_round_trips_and_tolerates_junk() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("ui_state.json");
+        assert_eq!(UiState::read(&path), UiState::default());
+
+        let state = UiState { view: View::Mods, selected_id: Some(3) };
+        state.write(&path).unwrap();
+        assert_eq!(UiState::read(&path), state);
+
+        fs::write(&path, r#"{"view": "gallery", "selected_id": 3}"#).unwrap();
+        assert_eq!(UiState::read(&path), UiState::default());
+    }
+}
@@ -0,0 +1,133 @@
+//! worlds.rs – which game version last saved each world of an instance
+//!
+//! Saves are SQLite files the launcher can't read, so it keeps its own
+//! record: whenever a game it started exits, every world saved during the
+//! session is stamped with the instance's version in `<data>/worlds.json`.
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    pages::{instances::Instance, versions},
+    schema::{self, Versioned},
+};
+
+/// world file name → game version that last saved it
+#[derive(Serialize, Deserialize, Default)]
+pub struct WorldVersions {
+    #[serde(default)]
+    pub worlds: BTreeMap<String, String>,
+}
+
+impl Versioned for WorldVersions {
+    const SCHEMA: u64 = 1;
+}
+
+impl WorldVersions {
+    fn path(inst: &Instance) -> PathBuf {
+        inst.data_dir().join("worlds.json")
+    }
+
+    /// The instance's record; empty if there is none.
+    pub fn load(inst: &Instance) -> Self {
+        schema::load(&Self::path(inst))
+    }
+
+    fn save(&self, inst: &Instance) -> io::Result<()> {
+        schema::save(&Self::path(inst), self)
+    }
+
+    pub fn version_of(&self, world: &Path) -> Option<&str> {
+        let name = world.file_name()?.to_str()?;
+        self.worlds.get(name).map(String::as_str)
+    }
+
+    /// Stamps every world modified since `since` with the instance's version;
+    /// returns how many were stamped.
+    pub fn stamp_saved_since(inst: &Instance, since: SystemTime) -> io::Result<usize> {
+        let saved: Vec<String> = inst
+            .worlds()
+            .into_iter()
+            .filter(|w| fs::metadata(w).and_then(|m| m.modified()).is_ok_and(|t| t >= since))
+            .filter_map(|w| w.file_name()?.to_str().map(str::to_owned))
+            .collect();
+        if saved.is_empty() {
+            return Ok(0);
+        }
+        let mut record = Self::load(inst);
+        for name in &saved {
+            record.worlds.insert(name.clone(), inst.version.clone());
+        }
+        record.save(inst)?;
+        Ok(saved.len())
+    }
+}
+
+/// Why opening a world last saved by `world_ver` with game `game_ver` is
+/// risky: any downgrade, or an upgrade across a major/minor release (which
+/// older versions can't undo). `None` when fine or either can't be parsed.
+pub fn compatibility(world_ver: &str, game_ver: &str) -> Option<String> {
+    let (world, game) = (versions::parse_semver(world_ver)?, versions::parse_semver(game_ver)?);
+    if game < world {
+        return Some(format!(
+            "It was last saved by v{world_ver}; the older v{game_ver} may refuse to load it or damage it."
+        ));
+    }
+    if (game.major, game.minor) != (world.major, world.minor) {
+        return Some(format!(
+            "It was last saved by v{world_ver}; v{game_ver} will upgrade it and older versions won't open it again. Back it up first."
+        ));
+    }
+    None
+}
+
+/// Warning for launching `inst` straight into `world`, if its recorded version calls for one.
+pub fn launch_warning(inst: &Instance, world: &Path) -> Option<String> {
+    let record = WorldVersions::load(inst);
+    compatibility(record.version_of(world)?, &inst.version)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::testutil;
+
+    #[test]
+    fn same_minor_is_fine() {
+        assert_eq!(compatibility("1.20.3", "1.20.3"), None);
+        assert_eq!(compatibility("1.20.3", "1.20.11"), None);
+        assert_eq!(compatibility("1.20.3", "junk"), None);
+    }
+
+    #[test]
+    fn downgrades_warn() {
+        assert!(compatibility("1.20.3", "1.20.1").unwrap().contains("older"));
+        assert!(compatibility("1.21.0", "1.21.0-rc.2").is_some());
+    }
+
+    #[test]
+    fn minor_upgrades_warn() {
+        assert!(compatibility("1.19.8", "1.20.0").unwrap().contains("Back it up"));
+    }
+
+    #[test]
+    fn worlds_saved_during_the_session_get_stamped() {
+        let tmp = tempfile::tempdir().unwrap();
+        let inst = testutil::instance(tmp.path());
+        fs::create_dir_all(inst.saves_dir()).unwrap();
+        let world = inst.saves_dir().join("Home.vcdbs");
+        fs::write(&world, b"").unwrap();
+
+        let later = SystemTime::now() + Duration::from_secs(60);
+        assert_eq!(WorldVersions::stamp_saved_since(&inst, later).unwrap(), 0);
+        assert_eq!(WorldVersions::stamp_saved_since(&inst, SystemTime::UNIX_EPOCH).unwrap(), 1);
+        assert_eq!(WorldVersions::load(&inst).version_of(&world), Some("1.20.0"));
+    }
+}